@@ -0,0 +1,258 @@
+use std::path::PathBuf;
+
+use clap::{Args, Parser, Subcommand};
+
+use crate::{
+    config::{Config, OutputFormat, CONFIG_FILE_PATH},
+    crypto::derivation::PassphraseSource,
+    utils::rate_limiter::validate_rate,
+};
+
+#[derive(Parser)]
+#[command(
+    name = "me-checker",
+    version,
+    about = "ME Foundation allocation checker"
+)]
+pub struct Cli {
+    /// Path to the config file
+    #[arg(long, global = true, default_value = CONFIG_FILE_PATH)]
+    pub config: PathBuf,
+
+    /// Runs the interactive menu when omitted
+    #[command(subcommand)]
+    pub command: Option<Command>,
+}
+
+#[derive(Subcommand)]
+pub enum Command {
+    /// Check allocation for every wallet in the secrets file
+    Check(CheckArgs),
+    /// Link every airdrop wallet to the claim wallet on the same line
+    Link(LinkArgs),
+    /// Print the address of every wallet in the secrets file
+    Derive(DeriveArgs),
+    /// Manage encrypted secrets vaults
+    Vault(VaultArgs),
+}
+
+#[derive(Args)]
+pub struct RunArgs {
+    /// Airdrop wallets secrets file
+    #[arg(long)]
+    pub secrets: Option<PathBuf>,
+
+    /// Proxies file
+    #[arg(long)]
+    pub proxies: Option<PathBuf>,
+
+    /// Connect directly instead of through the proxies file
+    #[arg(long)]
+    pub direct: bool,
+
+    /// Maximum number of API operations started per second, 0 disables the limit
+    #[arg(long, value_parser = parse_rate_limit)]
+    pub rate_limit: Option<f64>,
+
+    /// Maximum number of wallets processed concurrently
+    #[arg(long)]
+    pub parallelism: Option<usize>,
+
+    /// Run journal used to resume interrupted runs
+    #[arg(long)]
+    pub state: Option<PathBuf>,
+
+    /// Process every wallet again instead of skipping completed ones
+    #[arg(long)]
+    pub fresh: bool,
+
+    /// Allocation event to check or link against, repeat for several events
+    #[arg(long = "event", value_name = "EVENT")]
+    pub events: Vec<String>,
+
+    /// BIP39 passphrase of mnemonics without their own: none, prompt or env:VARIABLE
+    #[arg(long, value_name = "SOURCE")]
+    pub passphrase: Option<PassphraseSource>,
+
+    /// Password of encrypted secrets files: prompt or env:VARIABLE
+    #[arg(long, value_name = "SOURCE")]
+    pub vault_password: Option<PassphraseSource>,
+}
+
+#[derive(Args)]
+pub struct CheckArgs {
+    #[command(flatten)]
+    pub run: RunArgs,
+
+    /// File eligible wallets are appended to
+    #[arg(long)]
+    pub output: Option<PathBuf>,
+
+    /// File not eligible wallets are appended to
+    #[arg(long)]
+    pub not_eligible_output: Option<PathBuf>,
+
+    /// File wallets that could not be checked are appended to
+    #[arg(long)]
+    pub failed_output: Option<PathBuf>,
+
+    /// Format of the written results
+    #[arg(long, value_enum)]
+    pub format: Option<OutputFormat>,
+}
+
+#[derive(Args)]
+pub struct LinkArgs {
+    #[command(flatten)]
+    pub run: RunArgs,
+
+    /// Claim wallets secrets file
+    #[arg(long)]
+    pub claim_secrets: Option<PathBuf>,
+}
+
+#[derive(Args)]
+pub struct DeriveArgs {
+    /// Secrets file to derive addresses from
+    #[arg(long)]
+    pub secrets: Option<PathBuf>,
+
+    /// BIP39 passphrase of mnemonics without their own: none, prompt or env:VARIABLE
+    #[arg(long, value_name = "SOURCE")]
+    pub passphrase: Option<PassphraseSource>,
+
+    /// Password of encrypted secrets files: prompt or env:VARIABLE
+    #[arg(long, value_name = "SOURCE")]
+    pub vault_password: Option<PassphraseSource>,
+}
+
+#[derive(Args)]
+pub struct VaultArgs {
+    #[command(subcommand)]
+    pub command: VaultCommand,
+
+    /// Vault password: prompt or env:VARIABLE
+    #[arg(long, global = true, value_name = "SOURCE")]
+    pub password: Option<PassphraseSource>,
+}
+
+#[derive(Subcommand)]
+pub enum VaultCommand {
+    /// Encrypt a plaintext secrets file into a vault
+    Import(VaultImportArgs),
+    /// Print the address of every wallet in a vault
+    List(VaultListArgs),
+}
+
+#[derive(Args)]
+pub struct VaultImportArgs {
+    /// Plaintext secrets file to encrypt
+    pub input: PathBuf,
+
+    /// Vault file to create, the input file with the .vault extension by default
+    #[arg(long)]
+    pub output: Option<PathBuf>,
+
+    /// Delete the plaintext file once the vault is written
+    #[arg(long)]
+    pub remove_plaintext: bool,
+}
+
+#[derive(Args)]
+pub struct VaultListArgs {
+    /// Vault file to list
+    pub vault: PathBuf,
+}
+
+fn parse_rate_limit(value: &str) -> Result<f64, String> {
+    let rate = value.parse::<f64>().map_err(|e| e.to_string())?;
+    validate_rate(rate).map_err(|e| e.to_string())?;
+
+    Ok(rate)
+}
+
+impl RunArgs {
+    pub fn apply(self, config: &mut Config) {
+        if let Some(secrets) = self.secrets {
+            config.secrets_file = secrets;
+        }
+        if let Some(proxies) = self.proxies {
+            config.proxies_file = proxies;
+        }
+        if self.direct {
+            config.direct_connection = true;
+        }
+        if let Some(rate_limit) = self.rate_limit {
+            config.rate_limit = rate_limit;
+        }
+        if let Some(parallelism) = self.parallelism {
+            config.parallelism = parallelism;
+        }
+        if let Some(state) = self.state {
+            config.state_file = state;
+        }
+        if self.fresh {
+            config.resume = false;
+        }
+        if !self.events.is_empty() {
+            config.allocation_events = self.events;
+        }
+        if let Some(passphrase) = self.passphrase {
+            config.derivation.passphrase = passphrase;
+        }
+        if let Some(vault_password) = self.vault_password {
+            config.vault_password = vault_password;
+        }
+    }
+}
+
+impl CheckArgs {
+    pub fn apply(self, config: &mut Config) {
+        self.run.apply(config);
+        if let Some(output) = self.output {
+            config.eligible_file = output;
+        }
+        if let Some(not_eligible_output) = self.not_eligible_output {
+            config.not_eligible_file = not_eligible_output;
+        }
+        if let Some(failed_output) = self.failed_output {
+            config.failed_file = failed_output;
+        }
+        if let Some(format) = self.format {
+            config.output_format = format;
+        }
+    }
+}
+
+impl LinkArgs {
+    pub fn apply(self, config: &mut Config) {
+        self.run.apply(config);
+        if let Some(claim_secrets) = self.claim_secrets {
+            config.claim_secrets_file = claim_secrets;
+        }
+    }
+}
+
+impl DeriveArgs {
+    pub fn apply(self, config: &mut Config) {
+        if let Some(secrets) = self.secrets {
+            config.secrets_file = secrets;
+        }
+        if let Some(passphrase) = self.passphrase {
+            config.derivation.passphrase = passphrase;
+        }
+        if let Some(vault_password) = self.vault_password {
+            config.vault_password = vault_password;
+        }
+    }
+}
+
+impl VaultArgs {
+    pub fn apply(self, config: &mut Config) -> VaultCommand {
+        if let Some(password) = self.password {
+            config.vault_password = password;
+        }
+
+        self.command
+    }
+}
@@ -1,23 +1,161 @@
+use clap::ValueEnum;
 use serde::Deserialize;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
-const CONFIG_FILE_PATH: &str = "data/config.toml";
+use crate::{
+    crypto::derivation::{DerivationSettings, PassphraseSource},
+    me::{amount::MAX_DECIMALS, endpoints::Endpoints},
+    utils::{
+        constants::{
+            ALLOCATION_DECIMALS, ALLOCATION_EVENT, CHAIN, CHECK_REPORT_FILE_PATH,
+            CLAIM_SECRETS_FILE_PATH, ELIGIBLE_FILE_PATH, FAILED_FILE_PATH, HEADERS_FILE_PATH,
+            LINK_REPORT_FILE_PATH, NOT_ELIGIBLE_FILE_PATH, PROXIES_FILE_PATH, SECRETS_FILE_PATH,
+            STATE_FILE_PATH,
+        },
+        proxy_pool::ProxyPoolSettings,
+        rate_limiter::validate_rate,
+        retry::RetryPolicy,
+    },
+};
+
+pub const CONFIG_FILE_PATH: &str = "data/config.toml";
+
+#[derive(Deserialize, ValueEnum, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum OutputFormat {
+    #[default]
+    Text,
+    Jsonl,
+    Csv,
+}
 
 #[derive(Deserialize, Clone, Debug)]
 #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
 pub struct Config {
     pub parallelism: usize,
+    #[serde(default = "default_secrets_file")]
+    pub secrets_file: PathBuf,
+    #[serde(default = "default_claim_secrets_file")]
+    pub claim_secrets_file: PathBuf,
+    #[serde(default = "default_proxies_file")]
+    pub proxies_file: PathBuf,
+    #[serde(default = "default_eligible_file")]
+    pub eligible_file: PathBuf,
+    #[serde(default = "default_not_eligible_file")]
+    pub not_eligible_file: PathBuf,
+    #[serde(default = "default_failed_file")]
+    pub failed_file: PathBuf,
+    #[serde(default)]
+    pub output_format: OutputFormat,
+    #[serde(default = "default_check_report_file")]
+    pub check_report_file: PathBuf,
+    #[serde(default = "default_link_report_file")]
+    pub link_report_file: PathBuf,
+    #[serde(default = "default_state_file")]
+    pub state_file: PathBuf,
+    #[serde(default = "default_resume")]
+    pub resume: bool,
+    #[serde(default = "default_allocation_events")]
+    pub allocation_events: Vec<String>,
+    #[serde(default = "default_chain")]
+    pub chain: String,
+    #[serde(default = "default_allocation_decimals")]
+    pub allocation_decimals: u32,
+    #[serde(default)]
+    pub direct_connection: bool,
+    #[serde(default)]
+    pub rate_limit: f64,
+    #[serde(rename = "retry", default)]
+    pub retry_policy: RetryPolicy,
+    #[serde(rename = "proxy", default)]
+    pub proxy_pool: ProxyPoolSettings,
+    #[serde(default = "default_vault_password")]
+    pub vault_password: PassphraseSource,
+    #[serde(rename = "derivation", default)]
+    pub derivation: DerivationSettings,
+    #[serde(rename = "endpoints", default)]
+    pub endpoints: Endpoints,
+    #[serde(default = "default_headers_file")]
+    pub headers_file: PathBuf,
+}
+
+fn default_secrets_file() -> PathBuf {
+    PathBuf::from(SECRETS_FILE_PATH)
+}
+
+fn default_claim_secrets_file() -> PathBuf {
+    PathBuf::from(CLAIM_SECRETS_FILE_PATH)
+}
+
+fn default_proxies_file() -> PathBuf {
+    PathBuf::from(PROXIES_FILE_PATH)
+}
+
+fn default_eligible_file() -> PathBuf {
+    PathBuf::from(ELIGIBLE_FILE_PATH)
+}
+
+fn default_not_eligible_file() -> PathBuf {
+    PathBuf::from(NOT_ELIGIBLE_FILE_PATH)
+}
+
+fn default_failed_file() -> PathBuf {
+    PathBuf::from(FAILED_FILE_PATH)
+}
+
+fn default_check_report_file() -> PathBuf {
+    PathBuf::from(CHECK_REPORT_FILE_PATH)
+}
+
+fn default_link_report_file() -> PathBuf {
+    PathBuf::from(LINK_REPORT_FILE_PATH)
+}
+
+fn default_state_file() -> PathBuf {
+    PathBuf::from(STATE_FILE_PATH)
+}
+
+fn default_headers_file() -> PathBuf {
+    PathBuf::from(HEADERS_FILE_PATH)
+}
+
+fn default_vault_password() -> PassphraseSource {
+    PassphraseSource::Prompt
+}
+
+fn default_resume() -> bool {
+    true
+}
+
+fn default_allocation_events() -> Vec<String> {
+    vec![ALLOCATION_EVENT.to_string()]
+}
+
+fn default_chain() -> String {
+    CHAIN.to_string()
+}
+
+fn default_allocation_decimals() -> u32 {
+    ALLOCATION_DECIMALS
 }
 
 impl Config {
-    async fn read_from_file(path: impl AsRef<Path>) -> eyre::Result<Self> {
+    pub async fn read_from_file(path: impl AsRef<Path>) -> eyre::Result<Self> {
         let cfg_str = tokio::fs::read_to_string(path).await?;
-        Ok(toml::from_str(&cfg_str)?)
-    }
+        let config: Self = toml::from_str(&cfg_str)?;
+
+        config.endpoints.validate()?;
+
+        if config.allocation_decimals > MAX_DECIMALS {
+            eyre::bail!("ALLOCATION_DECIMALS must not exceed {MAX_DECIMALS}");
+        }
+
+        validate_rate(config.rate_limit).map_err(|e| eyre::eyre!("RATE_LIMIT {e}"))?;
+
+        if config.allocation_events.is_empty() {
+            eyre::bail!("ALLOCATION_EVENTS must contain at least one event");
+        }
 
-    pub async fn read_default() -> Self {
-        Self::read_from_file(CONFIG_FILE_PATH)
-            .await
-            .expect("Default config to be valid")
+        Ok(config)
     }
 }
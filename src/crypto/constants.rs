@@ -1,2 +1,5 @@
-pub const PATH: &str = "m/44'/501'/0'/0'";
 pub const CURVE: &str = "ed25519 seed";
+pub const ACCOUNT_SEGMENT: &str = "i";
+pub const PHANTOM_PATH: &str = "m/44'/501'/i'/0'";
+pub const LEGACY_PATH: &str = "m/44'/501'/i'";
+pub const LEDGER_PATH: &str = "m/44'/501'/i'";
@@ -0,0 +1,333 @@
+use std::str::FromStr;
+
+use serde::Deserialize;
+
+use super::constants::{ACCOUNT_SEGMENT, LEDGER_PATH, LEGACY_PATH, PHANTOM_PATH};
+
+const HARDENED_LIMIT: u32 = 0x80000000;
+
+#[derive(Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(try_from = "String")]
+pub struct AccountRange {
+    pub start: u32,
+    pub end: u32,
+}
+
+impl Default for AccountRange {
+    fn default() -> Self {
+        Self { start: 0, end: 1 }
+    }
+}
+
+impl AccountRange {
+    pub fn indices(&self) -> std::ops::Range<u32> {
+        self.start..self.end
+    }
+}
+
+impl FromStr for AccountRange {
+    type Err = eyre::Report;
+
+    fn from_str(range: &str) -> Result<Self, Self::Err> {
+        let index = |value: &str| {
+            value
+                .trim()
+                .parse::<u32>()
+                .ok()
+                .filter(|index| *index < HARDENED_LIMIT)
+                .ok_or_else(|| eyre::eyre!("Invalid account index `{value}` in `{range}`"))
+        };
+
+        let (start, end) = if let Some((start, end)) = range.split_once("..=") {
+            (index(start)?, index(end)? + 1)
+        } else if let Some((start, end)) = range.split_once("..") {
+            (index(start)?, index(end)?)
+        } else {
+            let account = index(range)?;
+            (account, account + 1)
+        };
+
+        if start >= end {
+            eyre::bail!("Account range `{range}` is empty");
+        }
+
+        Ok(Self { start, end })
+    }
+}
+
+impl TryFrom<String> for AccountRange {
+    type Error = eyre::Report;
+
+    fn try_from(range: String) -> Result<Self, Self::Error> {
+        range.parse()
+    }
+}
+
+#[derive(Deserialize, Clone, Debug, Default, PartialEq, Eq)]
+#[serde(try_from = "String")]
+pub enum DerivationScheme {
+    #[default]
+    Phantom,
+    Legacy,
+    Ledger,
+    Custom(String),
+}
+
+impl DerivationScheme {
+    fn template(&self) -> &str {
+        match self {
+            DerivationScheme::Phantom => PHANTOM_PATH,
+            DerivationScheme::Legacy => LEGACY_PATH,
+            DerivationScheme::Ledger => LEDGER_PATH,
+            DerivationScheme::Custom(template) => template,
+        }
+    }
+
+    pub fn path(&self, account: u32) -> String {
+        self.template()
+            .split('/')
+            .map(|segment| match segment.strip_prefix(ACCOUNT_SEGMENT) {
+                Some(hardened) => format!("{account}{hardened}"),
+                None => segment.to_string(),
+            })
+            .collect::<Vec<_>>()
+            .join("/")
+    }
+
+    pub fn paths(&self, accounts: AccountRange) -> Vec<String> {
+        accounts
+            .indices()
+            .map(|account| self.path(account))
+            .collect()
+    }
+}
+
+fn validate_template(template: &str) -> eyre::Result<()> {
+    let mut segments = template.split('/');
+
+    if segments.next() != Some("m") {
+        eyre::bail!("Derivation path must start with `m/`");
+    }
+
+    let mut depth = 0;
+    for segment in segments {
+        let position = depth + 1;
+        let Some(index) = segment.strip_suffix('\'') else {
+            eyre::bail!(
+                "Segment {position} of the derivation path must be hardened, ed25519 keys only support hardened derivation"
+            );
+        };
+
+        if index != ACCOUNT_SEGMENT
+            && index
+                .parse::<u32>()
+                .ok()
+                .is_none_or(|index| index >= HARDENED_LIMIT)
+        {
+            eyre::bail!("Invalid segment {position} in derivation path");
+        }
+
+        depth += 1;
+    }
+
+    if depth == 0 {
+        eyre::bail!("Derivation path has no segments");
+    }
+
+    Ok(())
+}
+
+impl FromStr for DerivationScheme {
+    type Err = eyre::Report;
+
+    fn from_str(scheme: &str) -> Result<Self, Self::Err> {
+        match scheme.trim().to_lowercase().as_str() {
+            "phantom" | "solflare" => Ok(Self::Phantom),
+            "legacy" => Ok(Self::Legacy),
+            "ledger" => Ok(Self::Ledger),
+            _ => {
+                let template = scheme.trim().replace(' ', "");
+                validate_template(&template).map_err(|e| {
+                    eyre::eyre!(
+                        "Unknown derivation scheme, expected phantom, solflare, legacy, ledger or a path like m/44'/501'/i'/0': {e}"
+                    )
+                })?;
+
+                Ok(Self::Custom(template))
+            }
+        }
+    }
+}
+
+impl TryFrom<String> for DerivationScheme {
+    type Error = eyre::Report;
+
+    fn try_from(scheme: String) -> Result<Self, Self::Error> {
+        scheme.parse()
+    }
+}
+
+#[derive(Deserialize, Clone, Debug, Default, PartialEq, Eq)]
+#[serde(try_from = "String")]
+pub enum PassphraseSource {
+    #[default]
+    None,
+    Prompt,
+    Env(String),
+}
+
+impl FromStr for PassphraseSource {
+    type Err = eyre::Report;
+
+    fn from_str(source: &str) -> Result<Self, Self::Err> {
+        match source.trim() {
+            "" | "none" => Ok(Self::None),
+            "prompt" => Ok(Self::Prompt),
+            source => match source.strip_prefix("env:") {
+                Some(name) if !name.trim().is_empty() => Ok(Self::Env(name.trim().to_string())),
+                _ => {
+                    eyre::bail!("Unknown passphrase source, expected none, prompt or env:VARIABLE")
+                }
+            },
+        }
+    }
+}
+
+impl TryFrom<String> for PassphraseSource {
+    type Error = eyre::Report;
+
+    fn try_from(source: String) -> Result<Self, Self::Error> {
+        source.parse()
+    }
+}
+
+#[derive(Deserialize, Clone, Debug, Default, PartialEq, Eq)]
+#[serde(default)]
+pub struct DerivationSettings {
+    pub accounts: AccountRange,
+    pub scheme: DerivationScheme,
+    pub passphrase: PassphraseSource,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct SecretLine<'a> {
+    pub secret: &'a str,
+    pub accounts: Option<AccountRange>,
+    pub scheme: Option<DerivationScheme>,
+    pub passphrase: Option<PassphraseSource>,
+}
+
+impl<'a> SecretLine<'a> {
+    pub fn parse(line: &'a str) -> eyre::Result<Self> {
+        let mut parts = line.split('|').map(str::trim);
+
+        let secret = parts.next().unwrap_or_default();
+        let accounts = parts
+            .next()
+            .filter(|accounts| !accounts.is_empty())
+            .map(str::parse)
+            .transpose()?;
+        let scheme = parts
+            .next()
+            .filter(|scheme| !scheme.is_empty())
+            .map(str::parse)
+            .transpose()?;
+        let passphrase = parts
+            .next()
+            .filter(|passphrase| !passphrase.is_empty())
+            .map(str::parse)
+            .transpose()?;
+
+        if parts.next().is_some() {
+            eyre::bail!(
+                "Expected `secret|accounts|scheme|passphrase`, found more than four `|` separated parts"
+            );
+        }
+
+        Ok(Self {
+            secret,
+            accounts,
+            scheme,
+            passphrase,
+        })
+    }
+
+    pub fn has_derivation(&self) -> bool {
+        self.accounts.is_some() || self.scheme.is_some() || self.passphrase.is_some()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_account_ranges() {
+        assert_eq!(
+            "0..20".parse::<AccountRange>().unwrap(),
+            AccountRange { start: 0, end: 20 }
+        );
+        assert_eq!(
+            "3..=4".parse::<AccountRange>().unwrap(),
+            AccountRange { start: 3, end: 5 }
+        );
+        assert_eq!(
+            "7".parse::<AccountRange>().unwrap(),
+            AccountRange { start: 7, end: 8 }
+        );
+
+        for range in ["5..5", "2..1", "a..3", "0..2147483648", "-1"] {
+            assert!(range.parse::<AccountRange>().is_err(), "{range}");
+        }
+    }
+
+    #[test]
+    fn renders_paths_of_every_scheme() {
+        assert_eq!(DerivationScheme::Phantom.path(3), "m/44'/501'/3'/0'");
+        assert_eq!(DerivationScheme::Legacy.path(3), "m/44'/501'/3'");
+        assert_eq!(DerivationScheme::Ledger.path(3), "m/44'/501'/3'");
+
+        let custom: DerivationScheme = "m/44'/501'/0'/i'".parse().unwrap();
+        assert_eq!(
+            custom.paths(AccountRange { start: 1, end: 3 }),
+            ["m/44'/501'/0'/1'", "m/44'/501'/0'/2'"]
+        );
+    }
+
+    #[test]
+    fn rejects_invalid_schemes() {
+        for scheme in ["trezor", "44'/501'/i'", "m/44'/501'/i", "m/44'/x'", "m"] {
+            assert!(scheme.parse::<DerivationScheme>().is_err(), "{scheme}");
+        }
+    }
+
+    #[test]
+    fn parses_secret_lines() {
+        let line = SecretLine::parse("word word word | 0..20 | legacy").unwrap();
+        assert_eq!(line.secret, "word word word");
+        assert_eq!(line.accounts, Some(AccountRange { start: 0, end: 20 }));
+        assert_eq!(line.scheme, Some(DerivationScheme::Legacy));
+
+        let line = SecretLine::parse("word word word||ledger").unwrap();
+        assert_eq!(line.accounts, None);
+        assert_eq!(line.scheme, Some(DerivationScheme::Ledger));
+
+        let line = SecretLine::parse("word word word|||env:SEED_PASSPHRASE").unwrap();
+        assert_eq!(
+            line.passphrase,
+            Some(PassphraseSource::Env("SEED_PASSPHRASE".to_string()))
+        );
+
+        assert!(!SecretLine::parse("base58key").unwrap().has_derivation());
+        assert!(SecretLine::parse("words|1|phantom|prompt|extra").is_err());
+
+        for (line, passphrase) in [
+            ("words|1|phantom|hunter2", "hunter2"),
+            ("words|1|hunter2", "hunter2"),
+            ("words|1|m/hunter2'", "hunter2"),
+        ] {
+            let error = SecretLine::parse(line).unwrap_err().to_string();
+            assert!(!error.contains(passphrase), "{error}");
+        }
+    }
+}
@@ -1,20 +1,23 @@
 use chrono::{SecondsFormat, Utc};
 
-pub fn get_verify_message(uuid: &str) -> String {
+pub fn get_verify_message(uri: &str, uuid: &str) -> String {
     let now = Utc::now().to_rfc3339_opts(SecondsFormat::Millis, true);
 
     format!(
-        "URI: mefoundation.com\nChain ID: sol\nNonce: {}\nIssued At: {}",
-        uuid, now
+        "URI: {}\nChain ID: sol\nNonce: {}\nIssued At: {}",
+        uri, uuid, now
     )
 }
 
-pub fn get_link_wallet_message(claim_wallet: &str, target_wallet: &str) -> String {
+pub fn get_link_wallet_message(
+    uri: &str,
+    claim_wallet: &str,
+    target_wallet: &str,
+    chain: &str,
+) -> String {
     let now = Utc::now().to_rfc3339_opts(SecondsFormat::Millis, true);
     format!(
-            "URI: mefoundation.com\nIssued At: {}\nChain ID: sol\nAllocation Wallet: {}\nClaim Wallet: {}",
-            now,
-            target_wallet,
-            claim_wallet
-        )
+        "URI: {}\nIssued At: {}\nChain ID: {}\nAllocation Wallet: {}\nClaim Wallet: {}",
+        uri, now, chain, target_wallet, claim_wallet
+    )
 }
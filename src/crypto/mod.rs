@@ -1,3 +1,5 @@
 mod constants;
+pub mod derivation;
 pub mod messages;
 pub mod signer;
+pub mod vault;
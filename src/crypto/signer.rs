@@ -1,21 +1,100 @@
-use solana_sdk::{signature::Keypair, signer::Signer as SolSigner};
-use std::num::ParseIntError;
+use solana_sdk::{
+    signature::Keypair,
+    signer::{keypair::keypair_from_seed, Signer as SolSigner},
+};
+use std::{num::ParseIntError, path::Path};
 
 use bip39::Mnemonic;
-use ring::hmac::Key;
-use sodiumoxide::crypto::sign::{ed25519, PublicKey, SecretKey, Seed};
+use hmac::{Hmac, KeyInit, Mac};
+use sha2::Sha512;
+use zeroize::{Zeroize, ZeroizeOnDrop, Zeroizing};
 
-use super::constants::{CURVE, PATH};
+use super::{
+    constants::CURVE,
+    derivation::{DerivationSettings, PassphraseSource, SecretLine},
+};
 
-pub fn get_wallet(secret: &str) -> eyre::Result<Keypair> {
-    match mnemonic_to_private_key(secret) {
-        Ok(private_key) => Ok(Keypair::from_base58_string(&private_key)),
-        Err(_) => {
-            let decoded_key = solana_sdk::bs58::decode(secret)
+const SEED_LENGTH: usize = 32;
+const KEYPAIR_LENGTH: usize = 64;
+const UNRECOGNIZED_KEY: &str = "Invalid private key: expected a mnemonic, a base58 or hex key, a JSON byte array or a keypair file path";
+
+pub fn get_wallets(
+    line: &str,
+    settings: &DerivationSettings,
+    passphrase: impl FnOnce(Option<&PassphraseSource>) -> eyre::Result<Zeroizing<String>>,
+) -> eyre::Result<Vec<Keypair>> {
+    let line = SecretLine::parse(line)?;
+    let secret = line.secret;
+
+    match Mnemonic::parse(secret) {
+        Ok(mnemonic) => {
+            let accounts = line.accounts.unwrap_or(settings.accounts);
+            let scheme = line.scheme.as_ref().unwrap_or(&settings.scheme);
+            let passphrase = passphrase(line.passphrase.as_ref())?;
+
+            mnemonic_to_keypairs(&mnemonic, &passphrase, &scheme.paths(accounts))
+        }
+        Err(e) if line.has_derivation() => eyre::bail!("Invalid mnemonic: {e}"),
+        Err(e) => match parse_private_key(secret) {
+            Ok(keypair) => Ok(vec![keypair]),
+            Err(_) if secret.split_whitespace().count() > 1 => {
+                eyre::bail!("Invalid mnemonic: {e}")
+            }
+            Err(key_error) => Err(key_error),
+        },
+    }
+}
+
+fn parse_private_key(secret: &str) -> eyre::Result<Keypair> {
+    let hex_key = secret.strip_prefix("0x").unwrap_or(secret);
+
+    let bytes = if secret.starts_with('[') {
+        parse_byte_array(secret)?
+    } else if Path::new(secret).is_file() {
+        let contents = Zeroizing::new(
+            std::fs::read_to_string(secret)
+                .map_err(|e| eyre::eyre!("Failed to read keypair file {secret}: {e}"))?,
+        );
+        parse_byte_array(&contents)
+            .map_err(|e| eyre::eyre!("Invalid keypair file {secret}: {e}"))?
+    } else if matches!(hex_key.len(), 64 | 128) && hex_key.chars().all(|c| c.is_ascii_hexdigit()) {
+        Zeroizing::new(hex::decode(hex_key).map_err(|_| eyre::eyre!(UNRECOGNIZED_KEY))?)
+    } else {
+        Zeroizing::new(
+            solana_sdk::bs58::decode(secret)
                 .into_vec()
-                .map_err(|_| eyre::eyre!("Invalid private key: {secret}"))?;
-            Ok(Keypair::from_bytes(&decoded_key)?)
+                .map_err(|_| eyre::eyre!(UNRECOGNIZED_KEY))?,
+        )
+    };
+
+    keypair_from_bytes(&bytes)
+}
+
+fn parse_byte_array(json: &str) -> eyre::Result<Zeroizing<Vec<u8>>> {
+    serde_json::from_str(json).map(Zeroizing::new).map_err(|_| {
+        eyre::eyre!("Invalid JSON byte array key, expected 32 or 64 numbers from 0 to 255")
+    })
+}
+
+fn keypair_from_bytes(bytes: &[u8]) -> eyre::Result<Keypair> {
+    let seed_to_keypair =
+        |seed: &[u8]| keypair_from_seed(seed).map_err(|_| eyre::eyre!("Invalid private key seed"));
+
+    match bytes.len() {
+        SEED_LENGTH => seed_to_keypair(bytes),
+        KEYPAIR_LENGTH => {
+            let (seed, public_key) = bytes.split_at(SEED_LENGTH);
+            let keypair = seed_to_keypair(seed)?;
+
+            if keypair.pubkey().as_ref() != public_key {
+                eyre::bail!("Invalid keypair: public key does not match the secret key");
+            }
+
+            Ok(keypair)
         }
+        length => eyre::bail!(
+            "Invalid private key length: {length} bytes, expected {SEED_LENGTH} or {KEYPAIR_LENGTH}"
+        ),
     }
 }
 
@@ -29,38 +108,40 @@ pub fn sign_message(signer: &Keypair, message: &str) -> eyre::Result<String> {
     Ok(signature.to_string())
 }
 
-#[derive(Debug)]
+#[cfg(test)]
+pub fn verify_signature(address: &str, message: &str, signature: &str) -> eyre::Result<bool> {
+    use solana_sdk::{pubkey::Pubkey, signature::Signature};
+    use std::str::FromStr;
+
+    let public_key =
+        Pubkey::from_str(address).map_err(|e| eyre::eyre!("Invalid address {address}: {e}"))?;
+    let signature = Signature::from_str(signature)
+        .map_err(|e| eyre::eyre!("Invalid signature {signature}: {e}"))?;
+
+    Ok(signature.verify(public_key.as_ref(), message.as_bytes()))
+}
+
+#[derive(Zeroize, ZeroizeOnDrop)]
 pub struct Keys {
     pub key: Vec<u8>,
     pub chain_code: Vec<u8>,
 }
 
-fn mnemonic_to_seed(mnemonic: &str) -> eyre::Result<Vec<u8>> {
-    let mnemonic = match Mnemonic::parse(mnemonic) {
-        Ok(mnemo) => mnemo,
-        Err(e) => eyre::bail!("{e}"),
-    };
-
-    let seed = mnemonic.to_seed("");
-    Ok(seed.to_vec())
-}
-
-fn to_hex(bytes: Vec<u8>) -> String {
-    hex::encode(bytes)
+fn mnemonic_to_seed(mnemonic: &Mnemonic, passphrase: &str) -> Zeroizing<[u8; 64]> {
+    Zeroizing::new(mnemonic.to_seed(passphrase))
 }
 
 fn replace_derive(val: &str) -> String {
     val.replace("'", "")
 }
 
-fn get_master_key_from_seed(seed: &str, curve: &str) -> Keys {
-    let seed_bytes = hex::decode(seed).expect("Invalid hex string");
+// Both the HMAC state and its output are wiped on drop, only the split halves outlive this call
+fn hmac_sha512_keys(key: &[u8], data: &[u8]) -> Keys {
+    let mut mac = Hmac::<Sha512>::new_from_slice(key).expect("HMAC accepts keys of any length");
+    mac.update(data);
 
-    let key = Key::new(ring::hmac::HMAC_SHA512, curve.as_bytes());
-    let tag = ring::hmac::sign(&key, &seed_bytes);
-
-    let result = tag.as_ref();
-    let (il, ir) = result.split_at(32);
+    let output = mac.finalize();
+    let (il, ir) = output.as_bytes().split_at(32);
 
     Keys {
         key: il.to_vec(),
@@ -68,6 +149,10 @@ fn get_master_key_from_seed(seed: &str, curve: &str) -> Keys {
     }
 }
 
+fn get_master_key_from_seed(seed: &[u8], curve: &str) -> Keys {
+    hmac_sha512_keys(curve.as_bytes(), seed)
+}
+
 fn parse_segments(path: &str) -> Result<Vec<u32>, ParseIntError> {
     path.split('/')
         .skip(1)
@@ -79,25 +164,19 @@ fn parse_segments(path: &str) -> Result<Vec<u32>, ParseIntError> {
 fn ckd_priv(keys: &Keys, index: u32) -> Keys {
     let index_buffer = index.to_be_bytes();
 
-    let mut data = vec![0u8];
+    // Sized up front so no reallocation leaves a copy of the parent key behind
+    let mut data = Zeroizing::new(Vec::with_capacity(1 + keys.key.len() + index_buffer.len()));
+    data.push(0u8);
     data.extend_from_slice(&keys.key);
     data.extend_from_slice(&index_buffer);
 
-    let key = Key::new(ring::hmac::HMAC_SHA512, &keys.chain_code);
-    let tag = ring::hmac::sign(&key, &data);
-
-    let result = tag.as_ref();
-    let (il, ir) = result.split_at(32);
-
-    Keys {
-        key: il.to_vec(),
-        chain_code: ir.to_vec(),
-    }
+    hmac_sha512_keys(&keys.chain_code, &data)
 }
 
-fn derive_path(path: &str, seed: &str, curve: &str, offset: u32) -> eyre::Result<Keys> {
+fn derive_path(path: &str, seed: &[u8], curve: &str, offset: u32) -> eyre::Result<Keys> {
     let master_keys = get_master_key_from_seed(seed, curve);
-    let segments = parse_segments(path).map_err(|e| e.to_string()).unwrap();
+    let segments =
+        parse_segments(path).map_err(|e| eyre::eyre!("Invalid derivation path `{path}`: {e}"))?;
 
     let derived_keys = segments
         .into_iter()
@@ -108,25 +187,191 @@ fn derive_path(path: &str, seed: &str, curve: &str, offset: u32) -> eyre::Result
     Ok(derived_keys)
 }
 
-fn derive(mnemonic: &str, path: &str, curve: &str) -> eyre::Result<Vec<u8>> {
-    let seed = mnemonic_to_seed(mnemonic)?;
-    let hex_seed = to_hex(seed);
+fn derive(seed: &[u8], path: &str, curve: &str) -> eyre::Result<Zeroizing<Vec<u8>>> {
+    let mut keys = derive_path(path, seed, curve, 0x80000000)?;
+
+    Ok(Zeroizing::new(std::mem::take(&mut keys.key)))
+}
 
-    let Keys { key, .. }: Keys = derive_path(path, &hex_seed, curve, 0x80000000)?;
+fn mnemonic_to_keypairs(
+    mnemonic: &Mnemonic,
+    passphrase: &str,
+    paths: &[String],
+) -> eyre::Result<Vec<Keypair>> {
+    let seed = mnemonic_to_seed(mnemonic, passphrase);
 
-    Ok(key)
+    paths
+        .iter()
+        .map(|path| keypair_from_bytes(&derive(seed.as_slice(), path, CURVE)?))
+        .collect()
 }
 
-pub fn mnemonic_to_private_key(mnemonic: &str) -> eyre::Result<String> {
-    sodiumoxide::init().unwrap();
-    let key = derive(mnemonic, PATH, CURVE)?;
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MNEMONIC: &str = "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about";
+
+    fn addresses(line: &str, settings: &DerivationSettings) -> Vec<String> {
+        get_wallets(line, settings, |_| Ok(Zeroizing::default()))
+            .unwrap()
+            .iter()
+            .map(get_address)
+            .collect()
+    }
+
+    #[test]
+    fn derives_the_phantom_account_by_default() {
+        assert_eq!(
+            addresses(MNEMONIC, &DerivationSettings::default()),
+            ["HAgk14JpMQLgt6rVgv7cBQFJWFto5Dqxi472uT3DKpqk"]
+        );
+    }
+
+    #[test]
+    fn derives_account_ranges_and_schemes_from_one_mnemonic() {
+        let phantom = addresses(&format!("{MNEMONIC}|0..3"), &Default::default());
+        assert_eq!(phantom.len(), 3);
+        assert_eq!(phantom[0], "HAgk14JpMQLgt6rVgv7cBQFJWFto5Dqxi472uT3DKpqk");
+        assert_ne!(phantom[1], phantom[2]);
+
+        let custom = addresses(
+            &format!("{MNEMONIC}|1..=2|m/44'/501'/i'/0'"),
+            &Default::default(),
+        );
+        assert_eq!(custom, phantom[1..]);
 
-    let seed: Seed = Seed::from_slice(&key).expect("Invalid private key");
-    let (public_key, _): (PublicKey, SecretKey) = ed25519::keypair_from_seed(&seed);
+        let legacy = addresses(&format!("{MNEMONIC}|0|legacy"), &Default::default());
+        assert_ne!(legacy[0], phantom[0]);
 
-    let mut secret_key: Vec<u8> = Vec::new();
-    secret_key.extend_from_slice(&key);
-    secret_key.extend_from_slice(public_key.as_ref());
+        let settings: DerivationSettings =
+            toml::from_str("accounts = \"0..2\"\nscheme = \"legacy\"").unwrap();
+        assert_eq!(
+            addresses(MNEMONIC, &settings),
+            [
+                legacy[0].clone(),
+                addresses(&format!("{MNEMONIC}|1|legacy"), &Default::default())[0].clone()
+            ]
+        );
+    }
+
+    #[test]
+    fn keeps_base58_keys_and_rejects_derivation_without_a_mnemonic() {
+        let keypair = Keypair::new();
+        let secret = keypair.to_base58_string();
+
+        assert_eq!(
+            addresses(&secret, &Default::default()),
+            [get_address(&keypair)]
+        );
+        assert!(
+            get_wallets(&format!("{secret}|0..2"), &Default::default(), |_| Ok(
+                Zeroizing::default()
+            ))
+            .is_err()
+        );
+    }
+
+    #[test]
+    fn detects_every_private_key_encoding() {
+        let keypair = Keypair::new();
+        let bytes = keypair.to_bytes();
+        let seed = &bytes[..SEED_LENGTH];
+
+        let dir = tempfile::tempdir().unwrap();
+        let keypair_file = dir.path().join("id.json");
+        std::fs::write(&keypair_file, format!("{:?}", bytes.to_vec())).unwrap();
+
+        for secret in [
+            keypair.to_base58_string(),
+            format!("{:?}", bytes.to_vec()),
+            format!("{:?}", seed.to_vec()),
+            solana_sdk::bs58::encode(seed).into_string(),
+            hex::encode(seed),
+            format!("0x{}", hex::encode(bytes)),
+            keypair_file.display().to_string(),
+        ] {
+            assert_eq!(
+                addresses(&secret, &Default::default()),
+                [get_address(&keypair)],
+                "{secret}"
+            );
+        }
+    }
 
-    Ok(solana_sdk::bs58::encode(secret_key.clone()).into_string())
+    #[test]
+    fn invalid_keys_are_reported_without_the_secret() {
+        let mut mismatched = Keypair::new().to_bytes();
+        mismatched[SEED_LENGTH..].copy_from_slice(&Keypair::new().pubkey().to_bytes());
+
+        for secret in [
+            "4vJ9JU1bJJE96FWSJKvHsmmFADCg4gpZQff4P3bkLKi0".to_string(),
+            solana_sdk::bs58::encode(&mismatched).into_string(),
+            format!("{:?}", [7u8, 200, 255]),
+            "[1, 2, 300]".to_string(),
+            hex::encode([42u8; 48]),
+            "abandon abandon zzzz".to_string(),
+        ] {
+            let error = get_wallets(&secret, &Default::default(), |_| Ok(Zeroizing::default()))
+                .unwrap_err()
+                .to_string();
+
+            assert!(!error.contains(&secret), "{error}");
+            assert!(!error.contains("300"), "{error}");
+        }
+    }
+
+    #[test]
+    fn passphrase_changes_the_derived_wallet() {
+        let settings = DerivationSettings::default();
+        let derive = |line: &str, expected: Option<PassphraseSource>| {
+            get_wallets(line, &settings, |source| {
+                assert_eq!(source, expected.as_ref());
+                Ok(Zeroizing::new("TREZOR".to_string()))
+            })
+            .unwrap()
+            .iter()
+            .map(get_address)
+            .collect::<Vec<_>>()
+        };
+
+        let global = derive(MNEMONIC, None);
+        assert_ne!(global, addresses(MNEMONIC, &settings));
+
+        let per_secret = derive(
+            &format!("{MNEMONIC}|||env:SEED_PASSPHRASE"),
+            Some(PassphraseSource::Env("SEED_PASSPHRASE".to_string())),
+        );
+        assert_eq!(per_secret, global);
+    }
+
+    #[test]
+    fn signed_message_verifies_against_signer_address() {
+        let signer = Keypair::new();
+        let message = "URI: mefoundation.com\nChain ID: sol";
+        let signature = sign_message(&signer, message).unwrap();
+
+        assert!(verify_signature(&get_address(&signer), message, &signature).unwrap());
+    }
+
+    #[test]
+    fn signature_does_not_verify_for_other_address_or_message() {
+        let signer = Keypair::new();
+        let other = Keypair::new();
+        let message = "URI: mefoundation.com\nChain ID: sol";
+        let signature = sign_message(&signer, message).unwrap();
+
+        assert!(!verify_signature(&get_address(&other), message, &signature).unwrap());
+        assert!(!verify_signature(&get_address(&signer), "Chain ID: sol", &signature).unwrap());
+    }
+
+    #[test]
+    fn malformed_address_or_signature_is_an_error() {
+        let signer = Keypair::new();
+        let address = get_address(&signer);
+        let signature = sign_message(&signer, "message").unwrap();
+
+        assert!(verify_signature("not-an-address", "message", &signature).is_err());
+        assert!(verify_signature(&address, "message", "not-a-signature").is_err());
+    }
 }
@@ -0,0 +1,171 @@
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+use sodiumoxide::crypto::{
+    pwhash::argon2id13::{self, MemLimit, OpsLimit, Salt},
+    secretbox::xsalsa20poly1305::{self as secretbox, Key, Nonce, KEYBYTES},
+};
+use zeroize::{Zeroize, Zeroizing};
+
+const VAULT_VERSION: u32 = 1;
+const VAULT_KDF: &str = "argon2id13";
+const VAULT_CIPHER: &str = "xsalsa20poly1305";
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct Vault {
+    version: u32,
+    kdf: String,
+    ops_limit: usize,
+    mem_limit: usize,
+    salt: String,
+    cipher: String,
+    nonce: String,
+    ciphertext: String,
+}
+
+fn derive_key(
+    password: &str,
+    salt: &Salt,
+    ops_limit: OpsLimit,
+    mem_limit: MemLimit,
+) -> eyre::Result<Key> {
+    let mut key = Key([0; KEYBYTES]);
+
+    argon2id13::derive_key(&mut key.0, password.as_bytes(), salt, ops_limit, mem_limit)
+        .map_err(|_| eyre::eyre!("Failed to derive the vault key, not enough memory"))?;
+
+    Ok(key)
+}
+
+impl Vault {
+    pub fn seal(secrets: &str, password: &str) -> eyre::Result<Self> {
+        sodiumoxide::init().map_err(|_| eyre::eyre!("Failed to initialize libsodium"))?;
+
+        let salt = argon2id13::gen_salt();
+        let ops_limit = argon2id13::OPSLIMIT_INTERACTIVE;
+        let mem_limit = argon2id13::MEMLIMIT_INTERACTIVE;
+        let key = derive_key(password, &salt, ops_limit, mem_limit)?;
+
+        let nonce = secretbox::gen_nonce();
+        let ciphertext = secretbox::seal(secrets.as_bytes(), &nonce, &key);
+
+        Ok(Self {
+            version: VAULT_VERSION,
+            kdf: VAULT_KDF.to_string(),
+            ops_limit: ops_limit.0,
+            mem_limit: mem_limit.0,
+            salt: hex::encode(salt.0),
+            cipher: VAULT_CIPHER.to_string(),
+            nonce: hex::encode(nonce.0),
+            ciphertext: hex::encode(ciphertext),
+        })
+    }
+
+    pub fn open(&self, password: &str) -> eyre::Result<Zeroizing<String>> {
+        sodiumoxide::init().map_err(|_| eyre::eyre!("Failed to initialize libsodium"))?;
+
+        if self.version != VAULT_VERSION || self.kdf != VAULT_KDF || self.cipher != VAULT_CIPHER {
+            eyre::bail!(
+                "Unsupported vault version {} ({}, {})",
+                self.version,
+                self.kdf,
+                self.cipher
+            );
+        }
+
+        let corrupted = || eyre::eyre!("Vault is corrupted");
+        let salt = hex::decode(&self.salt)
+            .ok()
+            .and_then(|salt| Salt::from_slice(&salt))
+            .ok_or_else(corrupted)?;
+        let nonce = hex::decode(&self.nonce)
+            .ok()
+            .and_then(|nonce| Nonce::from_slice(&nonce))
+            .ok_or_else(corrupted)?;
+        let ciphertext = hex::decode(&self.ciphertext).map_err(|_| corrupted())?;
+
+        let key = derive_key(
+            password,
+            &salt,
+            OpsLimit(self.ops_limit),
+            MemLimit(self.mem_limit),
+        )?;
+        let secrets = secretbox::open(&ciphertext, &nonce, &key)
+            .map_err(|_| eyre::eyre!("Wrong vault password or the vault was modified"))?;
+
+        String::from_utf8(secrets).map(Zeroizing::new).map_err(|e| {
+            e.into_bytes().zeroize();
+            corrupted()
+        })
+    }
+
+    pub fn parse(contents: &str) -> eyre::Result<Option<Self>> {
+        if !contents.trim_start().starts_with('{') {
+            return Ok(None);
+        }
+
+        serde_json::from_str(contents)
+            .map(Some)
+            .map_err(|_| eyre::eyre!("Invalid vault file"))
+    }
+
+    pub async fn write(&self, path: impl AsRef<Path>) -> eyre::Result<()> {
+        let path = path.as_ref();
+
+        let mut file = tokio::fs::OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .open(path)
+            .await
+            .map_err(|e| eyre::eyre!("Failed to create vault {}: {e}", path.display()))?;
+
+        tokio::io::AsyncWriteExt::write_all(
+            &mut file,
+            serde_json::to_string_pretty(self)?.as_bytes(),
+        )
+        .await?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SECRETS: &str = "first secret\nword word word|0..2|legacy\n";
+
+    #[test]
+    fn opens_a_sealed_vault_with_its_password() {
+        let vault = Vault::seal(SECRETS, "correct horse").unwrap();
+
+        let parsed = Vault::parse(&serde_json::to_string(&vault).unwrap())
+            .unwrap()
+            .unwrap();
+        assert_eq!(parsed, vault);
+        assert!(!parsed.ciphertext.contains(&hex::encode("first secret")));
+
+        assert_eq!(parsed.open("correct horse").unwrap().as_str(), SECRETS);
+    }
+
+    #[test]
+    fn rejects_wrong_passwords_and_modified_vaults() {
+        let mut vault = Vault::seal(SECRETS, "correct horse").unwrap();
+        assert!(vault.open("battery staple").is_err());
+
+        let last = vault.ciphertext.pop().unwrap();
+        vault.ciphertext.push(if last == '0' { '1' } else { '0' });
+        assert!(vault.open("correct horse").is_err());
+    }
+
+    #[test]
+    fn plaintext_secrets_are_not_vaults() {
+        assert!(
+            Vault::parse("4vJ9JU1bJJE96FWSJKvHsmmFADCg4gpZQff4P3bkLKi\n")
+                .unwrap()
+                .is_none()
+        );
+        assert!(Vault::parse("[1, 2, 3]\n").unwrap().is_none());
+        assert!(Vault::parse("{\"version\": 1}").is_err());
+    }
+}
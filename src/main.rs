@@ -1,20 +1,29 @@
+mod cli;
 mod config;
 mod crypto;
 mod me;
 mod utils;
 
-use modules::menu;
+use std::process::ExitCode;
+
+use clap::Parser;
+use cli::Cli;
+use modules::run;
 use utils::logger::init_default_logger;
 
 mod modules;
 
 #[tokio::main]
-async fn main() -> eyre::Result<()> {
+async fn main() -> ExitCode {
+    let cli = Cli::parse();
+
     let _guard = init_default_logger();
 
-    if let Err(e) = menu().await {
-        tracing::error!("Execution stopped with an unexpected error: {e}");
+    match run(cli).await {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            tracing::error!("Execution stopped with an unexpected error: {e}");
+            ExitCode::FAILURE
+        }
     }
-
-    Ok(())
 }
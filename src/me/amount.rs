@@ -0,0 +1,129 @@
+use std::{cmp::Ordering, fmt};
+
+use serde::{Serialize, Serializer};
+
+// 10^38 is the largest power of ten that fits into u128
+pub const MAX_DECIMALS: u32 = 38;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TokenAmount {
+    pub raw: u128,
+    pub decimals: u32,
+}
+
+impl TokenAmount {
+    pub fn new(raw: u128, decimals: u32) -> Self {
+        Self { raw, decimals }
+    }
+
+    pub fn zero(decimals: u32) -> Self {
+        Self::new(0, decimals)
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.raw == 0
+    }
+
+    fn rescale(&self, decimals: u32) -> Option<u128> {
+        if self.raw == 0 {
+            return Some(0);
+        }
+
+        let factor = 10u128.checked_pow(decimals.checked_sub(self.decimals)?)?;
+        self.raw.checked_mul(factor)
+    }
+
+    pub fn checked_add(&self, other: &Self) -> Option<Self> {
+        let decimals = self.decimals.max(other.decimals);
+        let raw = self
+            .rescale(decimals)?
+            .checked_add(other.rescale(decimals)?)?;
+
+        Some(Self::new(raw, decimals))
+    }
+
+    pub fn cmp_amount(&self, other: &Self) -> Ordering {
+        let decimals = self.decimals.max(other.decimals);
+
+        // An amount that does not fit after rescaling is larger than any that does
+        match (self.rescale(decimals), other.rescale(decimals)) {
+            (Some(a), Some(b)) => a.cmp(&b),
+            (None, _) => Ordering::Greater,
+            (_, None) => Ordering::Less,
+        }
+    }
+}
+
+impl fmt::Display for TokenAmount {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let decimals = self.decimals as usize;
+        let digits = format!("{:0>width$}", self.raw, width = decimals + 1);
+        let (integer, fraction) = digits.split_at(digits.len() - decimals);
+        let fraction = fraction.trim_end_matches('0');
+
+        if fraction.is_empty() {
+            write!(f, "{integer}")
+        } else {
+            write!(f, "{integer}.{fraction}")
+        }
+    }
+}
+
+impl Serialize for TokenAmount {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn formats_base_units_exactly() {
+        assert_eq!(TokenAmount::new(1_500_000, 6).to_string(), "1.5");
+        assert_eq!(TokenAmount::new(2_000_000, 6).to_string(), "2");
+        assert_eq!(TokenAmount::new(1, 9).to_string(), "0.000000001");
+        assert_eq!(TokenAmount::new(0, 6).to_string(), "0");
+        assert_eq!(TokenAmount::new(42, 0).to_string(), "42");
+        assert_eq!(
+            TokenAmount::new(123_456_789_012_345_678_901_234_567, 18).to_string(),
+            "123456789.012345678901234567"
+        );
+    }
+
+    #[test]
+    fn adds_amounts_with_different_decimals_exactly() {
+        let total = TokenAmount::new(9_007_199_254_740_993, 6)
+            .checked_add(&TokenAmount::new(1, 9))
+            .unwrap();
+
+        assert_eq!(total, TokenAmount::new(9_007_199_254_740_993_001, 9));
+        assert_eq!(total.to_string(), "9007199254.740993001");
+    }
+
+    #[test]
+    fn compares_amounts_with_different_decimals() {
+        let half = TokenAmount::new(5, 1);
+
+        assert_eq!(
+            half.cmp_amount(&TokenAmount::new(500_000, 6)),
+            Ordering::Equal
+        );
+        assert_eq!(half.cmp_amount(&TokenAmount::new(1, 0)), Ordering::Less);
+        assert_eq!(
+            TokenAmount::new(u128::MAX, 0).cmp_amount(&TokenAmount::new(1, 9)),
+            Ordering::Greater
+        );
+    }
+
+    #[test]
+    fn overflowing_addition_is_none() {
+        assert!(TokenAmount::new(u128::MAX, 0)
+            .checked_add(&TokenAmount::new(1, 0))
+            .is_none());
+        assert!(TokenAmount::new(1, 0)
+            .checked_add(&TokenAmount::new(1, 40))
+            .is_none());
+    }
+}
@@ -1,8 +1,11 @@
-pub const VERIFY_AND_CREATE_SESSION: &str =
-    "https://api-mainnet.magiceden.io/v1/wallet/vestack/auth/verify-and-create-session";
+pub const ME_API_BASE_URL: &str = "https://api-mainnet.magiceden.io";
 
-pub const AUTH_SESSION: &str = "https://mefoundation.com/api/trpc/auth.session";
+pub const ME_FOUNDATION_BASE_URL: &str = "https://mefoundation.com";
 
-pub const AUTH_LINK_WALLET: &str = "https://mefoundation.com/api/trpc/auth.linkWallet";
+pub const VERIFY_AND_CREATE_SESSION: &str = "/v1/wallet/vestack/auth/verify-and-create-session";
 
-pub const WALLETS: &str = "https://mefoundation.com/wallets";
+pub const AUTH_SESSION: &str = "/api/trpc/auth.session";
+
+pub const AUTH_LINK_WALLET: &str = "/api/trpc/auth.linkWallet";
+
+pub const WALLETS: &str = "/wallets";
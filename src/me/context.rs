@@ -0,0 +1,7 @@
+use super::{endpoints::Endpoints, headers::HeaderProfiles};
+
+#[derive(Clone, Debug, Default)]
+pub struct ApiContext {
+    pub endpoints: Endpoints,
+    pub headers: HeaderProfiles,
+}
@@ -0,0 +1,81 @@
+use reqwest::Url;
+use serde::Deserialize;
+
+use super::constants::{
+    AUTH_LINK_WALLET, AUTH_SESSION, ME_API_BASE_URL, ME_FOUNDATION_BASE_URL,
+    VERIFY_AND_CREATE_SESSION, WALLETS,
+};
+
+#[derive(Deserialize, Clone, Debug)]
+#[serde(default)]
+pub struct Endpoints {
+    pub me_api: String,
+    pub me_foundation: String,
+}
+
+impl Default for Endpoints {
+    fn default() -> Self {
+        Self {
+            me_api: ME_API_BASE_URL.to_string(),
+            me_foundation: ME_FOUNDATION_BASE_URL.to_string(),
+        }
+    }
+}
+
+impl Endpoints {
+    pub fn validate(&self) -> eyre::Result<()> {
+        for (name, base) in [
+            ("me_api", &self.me_api),
+            ("me_foundation", &self.me_foundation),
+        ] {
+            let url = Url::parse(base)
+                .map_err(|e| eyre::eyre!("Invalid `{name}` endpoint `{base}`: {e}"))?;
+
+            if !matches!(url.scheme(), "http" | "https") || url.host_str().is_none() {
+                eyre::bail!("Invalid `{name}` endpoint `{base}`: expected an http(s) URL");
+            }
+        }
+
+        Ok(())
+    }
+
+    fn join(base: &str, path: &str) -> String {
+        format!("{}{}", base.trim_end_matches('/'), path)
+    }
+
+    fn host(base: &str) -> String {
+        let Some(url) = Url::parse(base).ok() else {
+            return base.to_string();
+        };
+
+        match (url.host_str(), url.port()) {
+            (Some(host), Some(port)) => format!("{host}:{port}"),
+            (Some(host), None) => host.to_string(),
+            (None, _) => base.to_string(),
+        }
+    }
+
+    pub fn me_api_host(&self) -> String {
+        Self::host(&self.me_api)
+    }
+
+    pub fn me_foundation_host(&self) -> String {
+        Self::host(&self.me_foundation)
+    }
+
+    pub fn verify_and_create_session(&self) -> String {
+        Self::join(&self.me_api, VERIFY_AND_CREATE_SESSION)
+    }
+
+    pub fn auth_session(&self) -> String {
+        Self::join(&self.me_foundation, AUTH_SESSION)
+    }
+
+    pub fn auth_link_wallet(&self) -> String {
+        Self::join(&self.me_foundation, AUTH_LINK_WALLET)
+    }
+
+    pub fn wallets(&self) -> String {
+        Self::join(&self.me_foundation, WALLETS)
+    }
+}
@@ -0,0 +1,101 @@
+use reqwest::StatusCode;
+use serde::Deserialize;
+
+use super::rsc::RscError;
+
+#[derive(Deserialize, Clone, Copy, PartialEq, Eq, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum ErrorClass {
+    Transport,
+    Proxy,
+    Http,
+    ServerError,
+    RateLimited,
+    Forbidden,
+    Unauthorized,
+    Deserialize,
+    SignatureRejected,
+    UnexpectedResponse,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum ApiError {
+    #[error("Transport error: {0}")]
+    Transport(#[source] reqwest::Error),
+
+    #[error("Proxy error: {0}")]
+    Proxy(String),
+
+    #[error("HTTP error: {status} - {body}")]
+    Http { status: StatusCode, body: String },
+
+    #[error("Rate limited: {status} - {body}")]
+    RateLimited { status: StatusCode, body: String },
+
+    #[error("Forbidden: {status} - {body}")]
+    Forbidden { status: StatusCode, body: String },
+
+    #[error("Unauthorized or session expired: {status} - {body}")]
+    Unauthorized { status: StatusCode, body: String },
+
+    #[error("Failed to deserialize response: {source}")]
+    Deserialize {
+        #[source]
+        source: serde_json::Error,
+        body: String,
+    },
+
+    #[error("Signature rejected: {0}")]
+    SignatureRejected(String),
+
+    #[error("Unexpected response: {0}")]
+    UnexpectedResponse(String),
+
+    #[error("Invalid wallets page: {0}")]
+    Rsc(#[from] RscError),
+}
+
+impl ApiError {
+    pub fn from_request_error(error: reqwest::Error, via_proxy: bool) -> Self {
+        if via_proxy && error.is_connect() {
+            return Self::Proxy(error.to_string());
+        }
+
+        Self::Transport(error)
+    }
+
+    pub fn from_status(status: StatusCode, body: String) -> Self {
+        match status {
+            StatusCode::TOO_MANY_REQUESTS => Self::RateLimited { status, body },
+            StatusCode::UNAUTHORIZED => Self::Unauthorized { status, body },
+            StatusCode::FORBIDDEN => Self::Forbidden { status, body },
+            StatusCode::PROXY_AUTHENTICATION_REQUIRED => Self::Proxy(format!("{status} - {body}")),
+            StatusCode::BAD_REQUEST if body.to_lowercase().contains("signature") => {
+                Self::SignatureRejected(body)
+            }
+            _ => Self::Http { status, body },
+        }
+    }
+
+    pub fn class(&self) -> ErrorClass {
+        match self {
+            Self::Transport(_) => ErrorClass::Transport,
+            Self::Proxy(_) => ErrorClass::Proxy,
+            Self::Http { status, .. } if status.is_server_error() => ErrorClass::ServerError,
+            Self::Http { .. } => ErrorClass::Http,
+            Self::RateLimited { .. } => ErrorClass::RateLimited,
+            Self::Forbidden { .. } => ErrorClass::Forbidden,
+            Self::Unauthorized { .. } => ErrorClass::Unauthorized,
+            Self::Deserialize { .. } => ErrorClass::Deserialize,
+            Self::SignatureRejected(_) => ErrorClass::SignatureRejected,
+            Self::UnexpectedResponse(_) | Self::Rsc(_) => ErrorClass::UnexpectedResponse,
+        }
+    }
+
+    pub fn should_rotate_proxy(&self) -> bool {
+        matches!(
+            self,
+            Self::Transport(_) | Self::Proxy(_) | Self::RateLimited { .. } | Self::Forbidden { .. }
+        )
+    }
+}
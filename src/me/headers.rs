@@ -0,0 +1,298 @@
+use std::path::Path;
+
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue};
+use serde::Deserialize;
+
+use super::endpoints::Endpoints;
+
+const DEFAULT_PROFILES: &str = include_str!("../../data/headers.toml");
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Variable {
+    MeApi,
+    MeApiHost,
+    MeFoundation,
+    TraceId,
+    SpanId,
+}
+
+impl Variable {
+    fn parse(name: &str) -> Option<Self> {
+        match name {
+            "me_api" => Some(Self::MeApi),
+            "me_api_host" => Some(Self::MeApiHost),
+            "me_foundation" => Some(Self::MeFoundation),
+            "trace_id" => Some(Self::TraceId),
+            "span_id" => Some(Self::SpanId),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum Segment {
+    Literal(String),
+    Variable(Variable),
+}
+
+#[derive(Clone, Debug)]
+struct Template {
+    segments: Vec<Segment>,
+}
+
+impl Template {
+    fn parse(template: &str) -> eyre::Result<Self> {
+        let mut segments = vec![];
+        let mut rest = template;
+
+        while let Some(start) = rest.find("{{") {
+            if start > 0 {
+                segments.push(Segment::Literal(rest[..start].to_string()));
+            }
+
+            let Some(end) = rest[start..].find("}}") else {
+                eyre::bail!("Unclosed placeholder in `{template}`");
+            };
+
+            let name = rest[start + 2..start + end].trim();
+            let variable = Variable::parse(name)
+                .ok_or_else(|| eyre::eyre!("Unknown placeholder `{name}` in `{template}`"))?;
+
+            segments.push(Segment::Variable(variable));
+            rest = &rest[start + end + 2..];
+        }
+
+        if !rest.is_empty() {
+            segments.push(Segment::Literal(rest.to_string()));
+        }
+
+        Ok(Self { segments })
+    }
+
+    fn render(&self, context: &RequestContext) -> String {
+        self.segments
+            .iter()
+            .map(|segment| match segment {
+                Segment::Literal(literal) => literal.as_str(),
+                Segment::Variable(variable) => context.value(*variable),
+            })
+            .collect()
+    }
+}
+
+struct RequestContext {
+    me_api: String,
+    me_api_host: String,
+    me_foundation: String,
+    trace_id: String,
+    span_id: String,
+}
+
+impl RequestContext {
+    fn new(endpoints: &Endpoints) -> Self {
+        Self {
+            me_api: endpoints.me_api.trim_end_matches('/').to_string(),
+            me_api_host: endpoints.me_api_host(),
+            me_foundation: endpoints.me_foundation.trim_end_matches('/').to_string(),
+            trace_id: format!("{:032x}", rand::random::<u128>()),
+            span_id: format!("{:016x}", rand::random::<u64>()),
+        }
+    }
+
+    fn value(&self, variable: Variable) -> &str {
+        match variable {
+            Variable::MeApi => &self.me_api,
+            Variable::MeApiHost => &self.me_api_host,
+            Variable::MeFoundation => &self.me_foundation,
+            Variable::TraceId => &self.trace_id,
+            Variable::SpanId => &self.span_id,
+        }
+    }
+}
+
+#[derive(Deserialize, Clone, Debug)]
+#[serde(try_from = "toml::Table")]
+pub struct HeaderProfile {
+    headers: Vec<(HeaderName, Template)>,
+}
+
+impl TryFrom<toml::Table> for HeaderProfile {
+    type Error = eyre::Report;
+
+    fn try_from(table: toml::Table) -> Result<Self, Self::Error> {
+        let headers = table
+            .into_iter()
+            .map(|(name, value)| {
+                let header_name = HeaderName::from_bytes(name.as_bytes())
+                    .map_err(|e| eyre::eyre!("Invalid header name `{name}`: {e}"))?;
+
+                let toml::Value::String(value) = value else {
+                    eyre::bail!("Value of header `{name}` must be a string");
+                };
+
+                let template = Template::parse(&value)?;
+                let literal_only = template
+                    .segments
+                    .iter()
+                    .filter_map(|segment| match segment {
+                        Segment::Literal(literal) => Some(literal.as_str()),
+                        Segment::Variable(_) => None,
+                    })
+                    .collect::<String>();
+
+                HeaderValue::from_str(&literal_only)
+                    .map_err(|e| eyre::eyre!("Invalid value of header `{name}`: {e}"))?;
+
+                Ok((header_name, template))
+            })
+            .collect::<eyre::Result<_>>()?;
+
+        Ok(Self { headers })
+    }
+}
+
+impl HeaderProfile {
+    pub fn render(&self, endpoints: &Endpoints) -> HeaderMap {
+        let context = RequestContext::new(endpoints);
+        let mut headers = HeaderMap::with_capacity(self.headers.len());
+
+        for (name, template) in &self.headers {
+            match HeaderValue::from_str(&template.render(&context)) {
+                Ok(value) => {
+                    headers.insert(name.clone(), value);
+                }
+                Err(e) => tracing::warn!("Skipping header {name}: {e}"),
+            }
+        }
+
+        headers
+    }
+}
+
+#[derive(Deserialize, Clone, Debug)]
+pub struct HeaderProfiles {
+    #[serde(default = "default_verify_and_create_session")]
+    pub verify_and_create_session: HeaderProfile,
+    #[serde(default = "default_auth_session")]
+    pub auth_session: HeaderProfile,
+    #[serde(default = "default_auth_link_wallet")]
+    pub auth_link_wallet: HeaderProfile,
+    #[serde(default = "default_wallets")]
+    pub wallets: HeaderProfile,
+}
+
+impl Default for HeaderProfiles {
+    fn default() -> Self {
+        toml::from_str(DEFAULT_PROFILES).expect("Built-in header profiles are valid")
+    }
+}
+
+fn default_verify_and_create_session() -> HeaderProfile {
+    HeaderProfiles::default().verify_and_create_session
+}
+
+fn default_auth_session() -> HeaderProfile {
+    HeaderProfiles::default().auth_session
+}
+
+fn default_auth_link_wallet() -> HeaderProfile {
+    HeaderProfiles::default().auth_link_wallet
+}
+
+fn default_wallets() -> HeaderProfile {
+    HeaderProfiles::default().wallets
+}
+
+impl HeaderProfiles {
+    pub async fn load(path: impl AsRef<Path>) -> eyre::Result<Self> {
+        let path = path.as_ref();
+
+        match tokio::fs::read_to_string(path).await {
+            Ok(contents) => toml::from_str(&contents)
+                .map_err(|e| eyre::eyre!("Invalid header profiles in {}: {e}", path.display())),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                tracing::info!(
+                    "Header profiles file {} not found, using built-in headers",
+                    path.display()
+                );
+                Ok(Self::default())
+            }
+            Err(e) => Err(e.into()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use reqwest::header::{HOST, REFERER};
+
+    use super::*;
+
+    fn endpoints() -> Endpoints {
+        Endpoints {
+            me_api: "http://127.0.0.1:8080/".to_string(),
+            me_foundation: "https://staging.mefoundation.com".to_string(),
+        }
+    }
+
+    #[test]
+    fn built_in_profiles_render_endpoint_placeholders() {
+        let profiles = HeaderProfiles::default();
+
+        let verify = profiles.verify_and_create_session.render(&endpoints());
+        assert_eq!(verify[HOST], "127.0.0.1:8080");
+
+        let wallets = profiles.wallets.render(&endpoints());
+        assert_eq!(wallets[REFERER], "https://staging.mefoundation.com/wallets");
+    }
+
+    #[test]
+    fn trace_id_is_shared_within_a_request_and_fresh_per_request() {
+        let profile = HeaderProfiles::default().auth_session;
+
+        let first = profile.render(&endpoints());
+        let second = profile.render(&endpoints());
+
+        let trace_id =
+            |headers: &HeaderMap| headers["sentry-trace"].to_str().unwrap()[..32].to_string();
+
+        assert!(first["baggage"]
+            .to_str()
+            .unwrap()
+            .contains(&format!("sentry-trace_id={}", trace_id(&first))));
+        assert_ne!(trace_id(&first), trace_id(&second));
+    }
+
+    #[test]
+    fn missing_profiles_fall_back_to_built_in_ones() {
+        let profiles: HeaderProfiles = toml::from_str(
+            r#"
+            [wallets]
+            user-agent = "custom/1.0 ({{me_api_host}})"
+            "#,
+        )
+        .unwrap();
+
+        let wallets = profiles.wallets.render(&endpoints());
+        assert_eq!(wallets.len(), 1);
+        assert_eq!(wallets["user-agent"], "custom/1.0 (127.0.0.1:8080)");
+
+        let auth_session = profiles.auth_session.render(&endpoints());
+        assert_eq!(auth_session["x-trpc-source"], "nextjs-react");
+    }
+
+    #[test]
+    fn rejects_unknown_placeholders_and_invalid_headers() {
+        for profile in [
+            "[wallets]\nrsc = \"{{release}}\"",
+            "[wallets]\nrsc = \"{{trace_id\"",
+            "[wallets]\n\"bad header\" = \"1\"",
+            "[wallets]\nrsc = 1",
+        ] {
+            assert!(
+                toml::from_str::<HeaderProfiles>(profile).is_err(),
+                "{profile}"
+            );
+        }
+    }
+}
@@ -0,0 +1,543 @@
+use std::{
+    collections::{HashMap, HashSet},
+    sync::{
+        atomic::{AtomicBool, AtomicUsize, Ordering},
+        Arc, Mutex,
+    },
+};
+
+use axum::{
+    extract::{Query, State},
+    http::{header, HeaderMap, StatusCode},
+    response::{IntoResponse, Response},
+    routing::{get, post},
+    Json, Router,
+};
+use serde_json::{json, Value};
+use tokio::{net::TcpListener, task::JoinHandle};
+use uuid::Uuid;
+
+use chrono::DateTime;
+
+use crate::crypto::signer::verify_signature;
+
+use super::constants::{AUTH_LINK_WALLET, AUTH_SESSION, VERIFY_AND_CREATE_SESSION, WALLETS};
+
+const SESSION_COOKIE: &str = "me_session";
+const VERIFY_CHAIN_ID: &str = "sol";
+const VERIFY_MESSAGE_LAYOUT: [&str; 4] = ["URI", "Chain ID", "Nonce", "Issued At"];
+const LINK_MESSAGE_LAYOUT: [&str; 5] = [
+    "URI",
+    "Issued At",
+    "Chain ID",
+    "Allocation Wallet",
+    "Claim Wallet",
+];
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LinkedWallet {
+    pub wallet: String,
+    pub claim_wallet: String,
+    pub allocation_event: String,
+}
+
+#[derive(Default)]
+pub struct MockState {
+    message_uri: String,
+    allocations: HashMap<String, u64>,
+    nonces: Mutex<HashSet<String>>,
+    sessions: Mutex<HashMap<String, String>>,
+    linked: Mutex<Vec<LinkedWallet>>,
+    rejections: Mutex<Vec<String>>,
+    wallet_pages: AtomicUsize,
+    failing_wallet_pages: AtomicBool,
+    logins: AtomicUsize,
+    rejected_login: AtomicUsize,
+}
+
+impl MockState {
+    pub fn linked(&self) -> Vec<LinkedWallet> {
+        self.linked.lock().unwrap().clone()
+    }
+
+    pub fn rejections(&self) -> Vec<String> {
+        self.rejections.lock().unwrap().clone()
+    }
+
+    pub fn wallet_pages(&self) -> usize {
+        self.wallet_pages.load(Ordering::Relaxed)
+    }
+
+    pub fn fail_wallet_pages(&self, failing: bool) {
+        self.failing_wallet_pages.store(failing, Ordering::Relaxed);
+    }
+
+    pub fn reject_nth_login(&self, login: usize) {
+        self.rejected_login.store(login, Ordering::Relaxed);
+    }
+
+    pub fn expire_sessions(&self) {
+        self.sessions.lock().unwrap().clear();
+    }
+
+    fn reject(&self, reason: String) -> String {
+        self.rejections.lock().unwrap().push(reason.clone());
+        reason
+    }
+
+    fn session_token(&self, headers: &HeaderMap) -> Option<String> {
+        let cookies = headers.get(header::COOKIE)?.to_str().ok()?;
+
+        let token = cookies.split(';').find_map(|cookie| {
+            cookie
+                .trim()
+                .strip_prefix(SESSION_COOKIE)
+                .and_then(|rest| rest.strip_prefix('='))
+        })?;
+
+        self.sessions
+            .lock()
+            .unwrap()
+            .contains_key(token)
+            .then(|| token.to_string())
+    }
+}
+
+pub struct MockServer {
+    pub base_url: String,
+    pub state: Arc<MockState>,
+    handle: JoinHandle<()>,
+}
+
+impl MockServer {
+    pub async fn start(allocations: HashMap<String, u64>) -> Self {
+        let listener = TcpListener::bind("127.0.0.1:0")
+            .await
+            .expect("Mock server to bind");
+        let address = listener.local_addr().unwrap();
+        let base_url = format!("http://{address}");
+
+        let state = Arc::new(MockState {
+            message_uri: address.to_string(),
+            allocations,
+            ..Default::default()
+        });
+
+        let router = Router::new()
+            .route(AUTH_SESSION, get(auth_session))
+            .route(VERIFY_AND_CREATE_SESSION, post(verify_and_create_session))
+            .route(AUTH_LINK_WALLET, post(auth_link_wallet))
+            .route(WALLETS, get(wallets))
+            .with_state(Arc::clone(&state));
+
+        let handle = tokio::spawn(async move {
+            axum::serve(listener, router).await.unwrap();
+        });
+
+        Self {
+            base_url,
+            state,
+            handle,
+        }
+    }
+}
+
+impl Drop for MockServer {
+    fn drop(&mut self) {
+        self.handle.abort();
+    }
+}
+
+fn trpc_result(json: Value) -> Json<Value> {
+    Json(json!([{ "result": { "data": { "json": json } } }]))
+}
+
+fn unauthorized() -> Response {
+    (
+        StatusCode::UNAUTHORIZED,
+        Json(json!([{ "error": { "json": { "message": "UNAUTHORIZED" } } }])),
+    )
+        .into_response()
+}
+
+fn parse_message<'a>(
+    message: &'a str,
+    layout: &[&'a str],
+    uri: &str,
+) -> Result<HashMap<&'a str, &'a str>, String> {
+    let lines: Vec<&str> = message.split('\n').collect();
+
+    if lines.len() != layout.len() {
+        return Err(format!(
+            "Expected {} message lines, got {}",
+            layout.len(),
+            lines.len()
+        ));
+    }
+
+    let fields = layout
+        .iter()
+        .zip(lines)
+        .map(|(key, line)| {
+            line.strip_prefix(key)
+                .and_then(|rest| rest.strip_prefix(": "))
+                .map(|value| (*key, value))
+                .ok_or_else(|| format!("Expected `{key}: ...` message line, got `{line}`"))
+        })
+        .collect::<Result<HashMap<_, _>, _>>()?;
+
+    if fields["URI"] != uri {
+        return Err(format!("Unexpected URI `{}`", fields["URI"]));
+    }
+
+    DateTime::parse_from_rfc3339(fields["Issued At"])
+        .map_err(|e| format!("Invalid Issued At `{}`: {e}", fields["Issued At"]))?;
+
+    Ok(fields)
+}
+
+fn check_signature(address: &str, message: &str, signature: &str) -> Result<(), String> {
+    match verify_signature(address, message, signature) {
+        Ok(true) => Ok(()),
+        Ok(false) => Err(format!("Signature does not match wallet {address}")),
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+async fn auth_session(
+    State(state): State<Arc<MockState>>,
+    Query(query): Query<HashMap<String, String>>,
+) -> Response {
+    let nonce = query
+        .get("input")
+        .and_then(|input| serde_json::from_str::<Value>(input).ok())
+        .and_then(|input| input["0"]["json"]["uuid"].as_str().map(str::to_string));
+
+    let Some(nonce) = nonce else {
+        return (StatusCode::BAD_REQUEST, "Missing session nonce").into_response();
+    };
+
+    state.nonces.lock().unwrap().insert(nonce);
+
+    trpc_result(Value::Null).into_response()
+}
+
+async fn verify_and_create_session(
+    State(state): State<Arc<MockState>>,
+    Json(body): Json<Value>,
+) -> Response {
+    let (Some(wallet), Some(signature), Some(message)) = (
+        body["wallet"].as_str(),
+        body["signature"].as_str(),
+        body["message"].as_str(),
+    ) else {
+        return (StatusCode::BAD_REQUEST, Json(json!({ "success": false }))).into_response();
+    };
+
+    let login = state.logins.fetch_add(1, Ordering::Relaxed) + 1;
+
+    let verified = parse_message(message, &VERIFY_MESSAGE_LAYOUT, &state.message_uri)
+        .and_then(|fields| {
+            if login == state.rejected_login.load(Ordering::Relaxed) {
+                return Err(format!("Login {login} rejected"));
+            }
+
+            if fields["Chain ID"] != VERIFY_CHAIN_ID {
+                return Err(format!("Unexpected Chain ID `{}`", fields["Chain ID"]));
+            }
+
+            if state.nonces.lock().unwrap().contains(fields["Nonce"]) {
+                Ok(())
+            } else {
+                Err(format!("Unknown nonce `{}`", fields["Nonce"]))
+            }
+        })
+        .and_then(|_| check_signature(wallet, message, signature));
+
+    if let Err(reason) = verified {
+        state.reject(reason);
+        return Json(json!({ "success": false })).into_response();
+    }
+
+    let token = Uuid::new_v4().to_string();
+    state
+        .sessions
+        .lock()
+        .unwrap()
+        .insert(token.clone(), wallet.to_string());
+
+    (
+        [(
+            header::SET_COOKIE,
+            format!("{SESSION_COOKIE}={token}; Path=/; HttpOnly"),
+        )],
+        Json(json!({ "success": true })),
+    )
+        .into_response()
+}
+
+async fn auth_link_wallet(
+    State(state): State<Arc<MockState>>,
+    headers: HeaderMap,
+    Json(body): Json<Value>,
+) -> Response {
+    let Some(token) = state.session_token(&headers) else {
+        return unauthorized();
+    };
+
+    let data = &body["0"]["json"];
+    let (Some(wallet), Some(message), Some(signature), Some(chain), Some(allocation_event)) = (
+        data["wallet"].as_str(),
+        data["message"].as_str(),
+        data["signature"].as_str(),
+        data["chain"].as_str(),
+        data["allocationEvent"].as_str(),
+    ) else {
+        return (StatusCode::BAD_REQUEST, "Malformed link wallet body").into_response();
+    };
+
+    let session_address = state.sessions.lock().unwrap()[&token].clone();
+
+    let verified = parse_message(message, &LINK_MESSAGE_LAYOUT, &state.message_uri)
+        .and_then(|fields| {
+            if fields["Chain ID"] != chain {
+                Err(format!(
+                    "Message chain does not match request chain `{chain}`"
+                ))
+            } else if fields["Allocation Wallet"] != wallet {
+                Err(format!(
+                    "Allocation Wallet `{}` does not match wallet {wallet}",
+                    fields["Allocation Wallet"]
+                ))
+            } else if fields["Claim Wallet"] != session_address {
+                Err(format!(
+                    "Claim Wallet `{}` does not match session wallet {session_address}",
+                    fields["Claim Wallet"]
+                ))
+            } else {
+                Ok(fields["Claim Wallet"].to_string())
+            }
+        })
+        .and_then(|claim_wallet| check_signature(wallet, message, signature).map(|_| claim_wallet));
+
+    let claim_wallet = match verified {
+        Ok(claim_wallet) => claim_wallet,
+        Err(reason) => {
+            let reason = state.reject(reason);
+            return (
+                StatusCode::BAD_REQUEST,
+                Json(json!([{ "error": { "json": { "message": format!("Invalid signature: {reason}") } } }])),
+            )
+                .into_response();
+        }
+    };
+
+    state.linked.lock().unwrap().push(LinkedWallet {
+        wallet: wallet.to_string(),
+        claim_wallet,
+        allocation_event: allocation_event.to_string(),
+    });
+
+    let eligibility = if state.allocations.contains_key(wallet) {
+        "eligible"
+    } else {
+        "not_eligible"
+    };
+
+    trpc_result(json!({ "eligibility": { "eligibility": eligibility } })).into_response()
+}
+
+async fn wallets(State(state): State<Arc<MockState>>, headers: HeaderMap) -> Response {
+    let Some(token) = state.session_token(&headers) else {
+        return unauthorized();
+    };
+
+    let claim_wallet = state.sessions.lock().unwrap()[&token].clone();
+    state.wallet_pages.fetch_add(1, Ordering::Relaxed);
+
+    if state.failing_wallet_pages.load(Ordering::Relaxed) {
+        return (StatusCode::INTERNAL_SERVER_ERROR, "Wallets page failed").into_response();
+    }
+
+    let wallets: Vec<Value> = state
+        .linked()
+        .iter()
+        .filter(|link| link.claim_wallet == claim_wallet)
+        .filter_map(|link| {
+            state.allocations.get(&link.wallet).map(|amount| {
+                json!({
+                    "wallet": link.wallet,
+                    "allocationEvent": link.allocation_event,
+                    "allocationAmount": amount,
+                    "eligibilityCategory": "trader",
+                    "claimStatus": "unclaimed",
+                })
+            })
+        })
+        .collect();
+
+    let payload = format!(
+        "1:I[\"(app-pages-browser)/./src/app/wallets/page.tsx\",[\"app/wallets/page\"],\"default\"]\n2:{}\n",
+        json!({ "wallets": wallets })
+    );
+
+    ([(header::CONTENT_TYPE, "text/x-component")], payload).into_response()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use reqwest::cookie::Jar;
+    use solana_sdk::signature::Keypair;
+
+    use crate::{
+        crypto::{
+            messages::{get_link_wallet_message, get_verify_message},
+            signer::{get_address, sign_message},
+        },
+        me::{
+            api::{auth_link_wallet, auth_session, verify_and_create_session},
+            context::ApiContext,
+            endpoints::Endpoints,
+            error::ApiError,
+        },
+        utils::fetch::HttpClient,
+    };
+
+    use super::*;
+
+    async fn login(signer: &Keypair, nonce: &str, api: &ApiContext, client: &HttpClient) -> bool {
+        let message = get_verify_message(&api.endpoints.me_foundation_host(), nonce);
+        let signature = sign_message(signer, &message).unwrap();
+
+        verify_and_create_session(&get_address(signer), &signature, &message, api, client)
+            .await
+            .unwrap()
+            .unwrap()
+            .success
+    }
+
+    fn setup(server: &MockServer) -> (ApiContext, HttpClient) {
+        let api = ApiContext {
+            endpoints: Endpoints {
+                me_api: server.base_url.clone(),
+                me_foundation: server.base_url.clone(),
+            },
+            ..Default::default()
+        };
+        let client = HttpClient::new(None, Arc::new(Jar::default()));
+
+        (api, client)
+    }
+
+    #[tokio::test]
+    async fn rejects_session_with_unknown_nonce() {
+        let server = MockServer::start(HashMap::new()).await;
+        let (api, client) = setup(&server);
+        let signer = Keypair::new();
+
+        assert!(!login(&signer, &Uuid::new_v4().to_string(), &api, &client).await);
+        assert_eq!(server.state.rejections().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn rejects_link_signed_by_another_wallet() {
+        let server = MockServer::start(HashMap::new()).await;
+        let (api, client) = setup(&server);
+        let claim_wallet = Keypair::new();
+        let target_wallet = Keypair::new();
+        let nonce = Uuid::new_v4().to_string();
+
+        auth_session(&nonce, &api, &client).await.unwrap();
+        assert!(login(&claim_wallet, &nonce, &api, &client).await);
+
+        let message = get_link_wallet_message(
+            &api.endpoints.me_foundation_host(),
+            &get_address(&claim_wallet),
+            &get_address(&target_wallet),
+            "sol",
+        );
+        let forged_signature = sign_message(&Keypair::new(), &message).unwrap();
+
+        let result = auth_link_wallet(
+            &message,
+            &get_address(&target_wallet),
+            &forged_signature,
+            "sol",
+            "tge-airdrop-final",
+            &api,
+            &client,
+        )
+        .await;
+
+        assert!(matches!(result, Err(ApiError::SignatureRejected(_))));
+        assert!(server.state.linked().is_empty());
+        assert_eq!(server.state.rejections().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn checks_the_link_chain_against_the_request() {
+        let server = MockServer::start(HashMap::new()).await;
+        let (api, client) = setup(&server);
+        let claim_wallet = Keypair::new();
+        let target_wallet = Keypair::new();
+        let target_address = get_address(&target_wallet);
+        let nonce = Uuid::new_v4().to_string();
+
+        auth_session(&nonce, &api, &client).await.unwrap();
+        assert!(login(&claim_wallet, &nonce, &api, &client).await);
+
+        let message = get_link_wallet_message(
+            &api.endpoints.me_foundation_host(),
+            &get_address(&claim_wallet),
+            &target_address,
+            "eth",
+        );
+        let signature = sign_message(&target_wallet, &message).unwrap();
+        let link = |chain| {
+            auth_link_wallet(
+                &message,
+                &target_address,
+                &signature,
+                chain,
+                "tge-airdrop-final",
+                &api,
+                &client,
+            )
+        };
+
+        assert!(matches!(
+            link("sol").await,
+            Err(ApiError::SignatureRejected(_))
+        ));
+        assert!(link("eth").await.is_ok());
+        assert_eq!(server.state.linked().len(), 1);
+        assert_eq!(server.state.rejections().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn rejects_messages_signed_for_another_domain() {
+        let server = MockServer::start(HashMap::new()).await;
+        let (api, client) = setup(&server);
+        let signer = Keypair::new();
+        let nonce = Uuid::new_v4().to_string();
+
+        auth_session(&nonce, &api, &client).await.unwrap();
+
+        let message = get_verify_message("mefoundation.com", &nonce);
+        let signature = sign_message(&signer, &message).unwrap();
+        let response =
+            verify_and_create_session(&get_address(&signer), &signature, &message, &api, &client)
+                .await
+                .unwrap()
+                .unwrap();
+
+        assert!(!response.success);
+        assert_eq!(
+            server.state.rejections(),
+            ["Unexpected URI `mefoundation.com`"]
+        );
+        assert!(login(&signer, &nonce, &api, &client).await);
+    }
+}
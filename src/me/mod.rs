@@ -1,5 +1,12 @@
+pub mod amount;
 pub mod api;
 mod constants;
+pub mod context;
+pub mod endpoints;
+pub mod error;
+pub mod headers;
+#[cfg(test)]
+pub mod mock;
+pub mod rsc;
 pub mod schemas;
 pub mod typedefs;
-pub mod utils;
@@ -0,0 +1,471 @@
+use serde_json::{Map, Value};
+
+use super::amount::{TokenAmount, MAX_DECIMALS};
+
+const AMOUNT_KEY: &str = "allocationAmount";
+const DECIMALS_KEYS: [&str; 2] = ["tokenDecimals", "decimals"];
+const WALLET_KEYS: [&str; 3] = ["wallet", "walletAddress", "address"];
+const EVENT_KEYS: [&str; 2] = ["allocationEvent", "event"];
+const CATEGORY_KEYS: [&str; 3] = ["eligibilityCategory", "allocationCategory", "category"];
+const CLAIM_STATUS_KEYS: [&str; 1] = ["claimStatus"];
+const CLAIMED_KEYS: [&str; 2] = ["isClaimed", "claimed"];
+const UNDEFINED: &str = "$undefined";
+
+#[derive(Debug, thiserror::Error)]
+pub enum RscError {
+    #[error("Empty RSC payload")]
+    Empty,
+
+    #[error("Malformed RSC row {row}: {reason}")]
+    MalformedRow { row: usize, reason: String },
+
+    #[error("Invalid JSON in RSC row `{id}`: {source}")]
+    InvalidJson {
+        id: String,
+        #[source]
+        source: serde_json::Error,
+    },
+
+    #[error("Invalid `{key}` value {value} in RSC row `{id}`")]
+    InvalidField {
+        id: String,
+        key: String,
+        value: String,
+    },
+
+    #[error("RSC payload reports a server error: {0}")]
+    ServerError(String),
+
+    #[error("No allocation found in RSC payload")]
+    NoAllocation,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum RowKind<'a> {
+    Json(&'a str),
+    Tagged(&'a str, &'a str),
+    Text(&'a str),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Row<'a> {
+    id: &'a str,
+    kind: RowKind<'a>,
+}
+
+fn parse_rows(payload: &str) -> Vec<Result<Row<'_>, RscError>> {
+    let mut rows = vec![];
+    let mut rest = payload;
+
+    while !rest.is_empty() {
+        if let Some(next) = rest.strip_prefix('\n') {
+            rest = next;
+            continue;
+        }
+
+        let (row, next) = parse_row(rest, rows.len() + 1);
+        rows.push(row);
+        rest = next;
+    }
+
+    rows
+}
+
+// A malformed row is skipped up to the end of its line so the rows after it still parse
+fn parse_row(input: &str, row: usize) -> (Result<Row<'_>, RscError>, &str) {
+    let line_end = input.find('\n').unwrap_or(input.len());
+    let malformed = |reason: &str| {
+        let error = RscError::MalformedRow {
+            row,
+            reason: reason.to_string(),
+        };
+
+        (Err(error), &input[line_end..])
+    };
+
+    let Some(colon) = input[..line_end].find(':') else {
+        return malformed("missing `:` after the row id");
+    };
+    let id = &input[..colon];
+
+    if !id.chars().all(|c| c.is_ascii_hexdigit()) {
+        return malformed(&format!("invalid row id `{id}`"));
+    }
+
+    let body = &input[colon + 1..];
+
+    if let Some(text) = body.strip_prefix('T') {
+        let Some(comma) = text.find(',') else {
+            return malformed("text row without a length");
+        };
+        let Ok(len) = usize::from_str_radix(&text[..comma], 16) else {
+            return malformed(&format!("invalid text length `{}`", &text[..comma]));
+        };
+        let start = comma + 1;
+        let Some(content) = text.get(start..start + len) else {
+            return malformed("text row is shorter than its length");
+        };
+
+        let row = Row {
+            id,
+            kind: RowKind::Text(content),
+        };
+
+        return (Ok(row), &text[start + len..]);
+    }
+
+    let end = body.find('\n').unwrap_or(body.len());
+    let content = &body[..end];
+
+    let tag_len = content
+        .find(|c: char| !c.is_ascii_uppercase())
+        .unwrap_or(content.len());
+
+    let kind = if tag_len == 0 {
+        RowKind::Json(content)
+    } else {
+        RowKind::Tagged(&content[..tag_len], &content[tag_len..])
+    };
+
+    (Ok(Row { id, kind }), &body[end..])
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AllocationRecord {
+    pub wallet: Option<String>,
+    pub event: Option<String>,
+    pub amount: u128,
+    pub decimals: Option<u32>,
+    pub category: Option<String>,
+    pub claim_status: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Allocations {
+    pub records: Vec<AllocationRecord>,
+}
+
+impl AllocationRecord {
+    pub fn token_amount(&self, default_decimals: u32) -> TokenAmount {
+        TokenAmount::new(self.amount, self.decimals.unwrap_or(default_decimals))
+    }
+}
+
+impl Allocations {
+    pub fn for_wallet(&self, wallet: &str, event: &str) -> Option<&AllocationRecord> {
+        let mut records = self.records.iter().filter(|record| {
+            record
+                .event
+                .as_deref()
+                .is_none_or(|record_event| record_event == event)
+        });
+
+        // A record without a wallet may be a group total, so it only counts when none names one
+        if self.records.iter().any(|record| record.wallet.is_some()) {
+            records.find(|record| record.wallet.as_deref() == Some(wallet))
+        } else {
+            records.next()
+        }
+    }
+}
+
+fn string_field(object: &Map<String, Value>, keys: &[&str]) -> Option<String> {
+    keys.iter()
+        .filter_map(|key| object.get(*key))
+        .find_map(Value::as_str)
+        .filter(|value| *value != UNDEFINED)
+        .map(str::to_string)
+}
+
+fn claim_status(object: &Map<String, Value>) -> Option<String> {
+    string_field(object, &CLAIM_STATUS_KEYS).or_else(|| {
+        CLAIMED_KEYS
+            .iter()
+            .filter_map(|key| object.get(*key))
+            .find_map(Value::as_bool)
+            .map(|claimed| if claimed { "claimed" } else { "unclaimed" }.to_string())
+    })
+}
+
+fn integer_field<T: TryFrom<u64> + std::str::FromStr>(
+    id: &str,
+    key: &str,
+    value: &Value,
+) -> Result<Option<T>, RscError> {
+    let invalid = || RscError::InvalidField {
+        id: id.to_string(),
+        key: key.to_string(),
+        value: value.to_string(),
+    };
+
+    match value {
+        Value::Null => Ok(None),
+        Value::String(value) if value == UNDEFINED => Ok(None),
+        Value::String(value) => value.parse().map(Some).map_err(|_| invalid()),
+        Value::Number(number) => number
+            .as_u64()
+            .and_then(|number| T::try_from(number).ok())
+            .map(Some)
+            .ok_or_else(invalid),
+        _ => Err(invalid()),
+    }
+}
+
+fn allocation_record(
+    id: &str,
+    object: &Map<String, Value>,
+) -> Result<Option<AllocationRecord>, RscError> {
+    let Some(amount) = object
+        .get(AMOUNT_KEY)
+        .map(|value| integer_field::<u128>(id, AMOUNT_KEY, value))
+        .transpose()?
+        .flatten()
+    else {
+        return Ok(None);
+    };
+
+    let decimals = DECIMALS_KEYS
+        .iter()
+        .find_map(|key| object.get(*key).map(|value| (key, value)))
+        .map(|(key, value)| match integer_field::<u32>(id, key, value)? {
+            Some(decimals) if decimals > MAX_DECIMALS => Err(RscError::InvalidField {
+                id: id.to_string(),
+                key: key.to_string(),
+                value: value.to_string(),
+            }),
+            decimals => Ok(decimals),
+        })
+        .transpose()?
+        .flatten();
+
+    Ok(Some(AllocationRecord {
+        wallet: string_field(object, &WALLET_KEYS),
+        event: string_field(object, &EVENT_KEYS),
+        amount,
+        decimals,
+        category: string_field(object, &CATEGORY_KEYS),
+        claim_status: claim_status(object),
+    }))
+}
+
+fn collect_allocations(
+    id: &str,
+    value: &Value,
+    records: &mut Vec<AllocationRecord>,
+) -> Result<(), RscError> {
+    match value {
+        Value::Object(object) => {
+            if let Some(record) = allocation_record(id, object)? {
+                records.push(record);
+            }
+
+            for value in object.values() {
+                collect_allocations(id, value, records)?;
+            }
+        }
+        Value::Array(items) => {
+            for item in items {
+                collect_allocations(id, item, records)?;
+            }
+        }
+        _ => {}
+    }
+
+    Ok(())
+}
+
+fn collect_row(row: Row<'_>, records: &mut Vec<AllocationRecord>) -> Result<(), RscError> {
+    match row.kind {
+        RowKind::Json(json) => {
+            let value: Value =
+                serde_json::from_str(json).map_err(|source| RscError::InvalidJson {
+                    id: row.id.to_string(),
+                    source,
+                })?;
+
+            let mut row_records = vec![];
+            collect_allocations(row.id, &value, &mut row_records)?;
+            records.extend(row_records);
+
+            Ok(())
+        }
+        RowKind::Tagged("E", error) => Err(RscError::ServerError(error.to_string())),
+        RowKind::Tagged(..) | RowKind::Text(_) => Ok(()),
+    }
+}
+
+pub fn parse_allocations(payload: &str) -> Result<Allocations, RscError> {
+    if payload.trim().is_empty() {
+        return Err(RscError::Empty);
+    }
+
+    let mut records = vec![];
+    let mut errors = vec![];
+
+    for row in parse_rows(payload) {
+        if let Err(e) = row.and_then(|row| collect_row(row, &mut records)) {
+            errors.push(e);
+        }
+    }
+
+    if records.is_empty() {
+        errors.sort_by_key(|e| !matches!(e, RscError::ServerError(_)));
+
+        return Err(errors.into_iter().next().unwrap_or(RscError::NoAllocation));
+    }
+
+    Ok(Allocations { records })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_rows_of_every_kind() {
+        let payload = concat!(
+            ":HL[\"/_next/static/css/app.css\",\"style\"]\n",
+            "0:[\"$\",\"div\",null,{}]\n1:I[\"app/page\",[],\"\"]\na:Tb,hello\nworld\n2:null\n"
+        );
+
+        let rows = parse_rows(payload)
+            .into_iter()
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+
+        assert_eq!(
+            rows.iter().map(|row| row.kind).collect::<Vec<_>>(),
+            [
+                RowKind::Tagged("HL", "[\"/_next/static/css/app.css\",\"style\"]"),
+                RowKind::Json("[\"$\",\"div\",null,{}]"),
+                RowKind::Tagged("I", "[\"app/page\",[],\"\"]"),
+                RowKind::Text("hello\nworld"),
+                RowKind::Json("null"),
+            ]
+        );
+    }
+
+    #[test]
+    fn finds_nested_allocations_with_their_details() {
+        let payload = concat!(
+            "1:I[\"app/wallets/page\",[],\"default\"]\n",
+            "2:[\"$\",\"$L3\",null,{\"wallets\":[",
+            "{\"walletAddress\":\"A\",\"allocationAmount\":1500000,\"tokenDecimals\":6,\"allocationEvent\":\"tge-airdrop-final\",",
+            "\"eligibilityCategory\":\"trader\",\"claimStatus\":\"claimable\"},",
+            "{\"wallet\":\"B\",\"allocationAmount\":\"98765432109876543210987\",\"decimals\":\"9\",\"isClaimed\":true},",
+            "{\"wallet\":\"C\",\"allocationAmount\":\"$undefined\"}",
+            "]}]\n"
+        );
+
+        let allocations = parse_allocations(payload).unwrap();
+
+        assert_eq!(
+            allocations.records,
+            [
+                AllocationRecord {
+                    wallet: Some("A".to_string()),
+                    event: Some("tge-airdrop-final".to_string()),
+                    amount: 1_500_000,
+                    decimals: Some(6),
+                    category: Some("trader".to_string()),
+                    claim_status: Some("claimable".to_string()),
+                },
+                AllocationRecord {
+                    wallet: Some("B".to_string()),
+                    event: None,
+                    amount: 98_765_432_109_876_543_210_987,
+                    decimals: Some(9),
+                    category: None,
+                    claim_status: Some("claimed".to_string()),
+                },
+            ]
+        );
+
+        assert_eq!(
+            allocations
+                .for_wallet("B", "season-2")
+                .map(|r| r.token_amount(6).to_string()),
+            Some("98765432109876.543210987".to_string())
+        );
+        assert!(allocations.for_wallet("A", "season-2").is_none());
+        assert!(allocations.for_wallet("C", "tge-airdrop-final").is_none());
+    }
+
+    #[test]
+    fn prefers_wallet_records_over_group_totals() {
+        let grouped = parse_allocations(concat!(
+            "2:{\"allocationAmount\":10,\"wallets\":[",
+            "{\"wallet\":\"A\",\"allocationAmount\":3},",
+            "{\"wallet\":\"B\",\"allocationAmount\":7}",
+            "]}\n"
+        ))
+        .unwrap();
+
+        let amount = |allocations: &Allocations, wallet| {
+            allocations
+                .for_wallet(wallet, "tge-airdrop-final")
+                .map(|record| record.amount)
+        };
+
+        assert_eq!(amount(&grouped, "A"), Some(3));
+        assert_eq!(amount(&grouped, "B"), Some(7));
+        assert_eq!(amount(&grouped, "C"), None);
+
+        let single = parse_allocations("2:{\"allocationAmount\":10}\n").unwrap();
+        assert_eq!(amount(&single, "A"), Some(10));
+    }
+
+    #[test]
+    fn skips_rows_it_does_not_understand() {
+        let payload = concat!(
+            ":HL[\"/_next/static/css/app.css\",\"style\"]\n",
+            "no row id here\n",
+            "1:{\"broken\":}\n",
+            "zz:{}\n",
+            "2:{\"wallet\":\"A\",\"allocationAmount\":7}\n"
+        );
+
+        let allocations = parse_allocations(payload).unwrap();
+
+        assert_eq!(
+            allocations
+                .for_wallet("A", "tge-airdrop-final")
+                .map(|record| record.amount),
+            Some(7)
+        );
+    }
+
+    #[test]
+    fn reports_descriptive_errors() {
+        assert!(matches!(parse_allocations(" \n"), Err(RscError::Empty)));
+        assert!(matches!(
+            parse_allocations("0:{\"user\":null}\n"),
+            Err(RscError::NoAllocation)
+        ));
+        assert!(matches!(
+            parse_allocations("0:null\n1:E{\"digest\":\"123\"}\n"),
+            Err(RscError::ServerError(error)) if error.contains("digest")
+        ));
+        assert!(matches!(
+            parse_allocations("zz:{}\n"),
+            Err(RscError::MalformedRow { row: 1, .. })
+        ));
+        assert!(matches!(
+            parse_allocations("0:{}\n1:T10,short"),
+            Err(RscError::MalformedRow { row: 2, .. })
+        ));
+        assert!(matches!(
+            parse_allocations("2:{\"allocationAmount\":}\n"),
+            Err(RscError::InvalidJson { id, .. }) if id == "2"
+        ));
+        assert!(matches!(
+            parse_allocations("2:{\"allocationAmount\":1.5}\n"),
+            Err(RscError::InvalidField { key, .. }) if key == "allocationAmount"
+        ));
+        assert!(matches!(
+            parse_allocations("2:{\"allocationAmount\":1,\"decimals\":4000000000}\n"),
+            Err(RscError::InvalidField { key, .. }) if key == "decimals"
+        ));
+        assert!(parse_allocations("2:{\"allocationAmount\":1,\"tokenDecimals\":\"38\"}\n").is_ok());
+    }
+}
@@ -61,13 +61,19 @@ pub struct LinkWalletData<'a> {
 }
 
 impl<'a> LinkWalletData<'a> {
-    pub fn new(message: &'a str, wallet: &'a str, signature: &'a str) -> Self {
+    pub fn new(
+        message: &'a str,
+        wallet: &'a str,
+        signature: &'a str,
+        chain: &'a str,
+        allocation_event: &'a str,
+    ) -> Self {
         Self {
             message,
-            chain: "sol",
+            chain,
             wallet,
             signature,
-            allocation_event: "tge-airdrop-final",
+            allocation_event,
             is_ledger: false,
         }
     }
@@ -91,8 +97,14 @@ pub struct LinkWalletBody<'a> {
 }
 
 impl<'a> LinkWalletBody<'a> {
-    pub fn new(message: &'a str, wallet: &'a str, signature: &'a str) -> Self {
-        let data = LinkWalletData::new(message, wallet, signature);
+    pub fn new(
+        message: &'a str,
+        wallet: &'a str,
+        signature: &'a str,
+        chain: &'a str,
+        allocation_event: &'a str,
+    ) -> Self {
+        let data = LinkWalletData::new(message, wallet, signature, chain, allocation_event);
         let json_wrapper = LinkWalletJsonWrapper::new(data);
 
         Self {
@@ -1,82 +1,149 @@
 use std::sync::Arc;
 
-use reqwest::{cookie::Jar, Proxy};
 use solana_sdk::signature::Keypair;
-use tokio::{fs::OpenOptions, sync::Mutex, task::JoinSet};
+use tokio::task::JoinSet;
+use tracing::Instrument;
 
 use crate::{
     config::Config,
-    crypto::signer::{get_address, get_wallet},
+    crypto::signer::get_address,
     utils::{
-        constants::{ELIGIBLE_FILE_PATH, PROXIES_FILE_PATH, SECRETS_FILE_PATH},
-        files::read_file_lines,
+        journal::{Journal, Operation, Status},
+        retry::RetryPolicy,
+        session::Session,
     },
 };
 
-use super::processor::{create_session, points};
+use super::{
+    load_api_context, load_proxy_pool, load_wallets,
+    processor::{create_session, points, LinkTarget},
+    results::{CheckResult, ResultSinks},
+    summary::RunSummary,
+};
 
 pub async fn checker(config: &Config) -> eyre::Result<()> {
-    let proxies: Vec<Proxy> = read_file_lines(PROXIES_FILE_PATH)
-        .await?
-        .iter()
-        .map(|proxy_url| Proxy::all(proxy_url).expect("Invalid proxy URL"))
-        .collect();
+    let proxy_pool = Arc::new(load_proxy_pool(config).await?);
+    let api = Arc::new(load_api_context(config).await?);
 
-    let proxies = Arc::new(proxies);
-    let proxies_len = proxies.len();
+    let all_wallets = load_wallets(&config.secrets_file, config).await?;
 
-    let all_wallets = read_file_lines(SECRETS_FILE_PATH).await?;
+    let journal = Journal::open(&config.state_file).await?;
+    if !config.resume {
+        journal.reset(Operation::Check).await?;
+    }
+    let journal = Arc::new(journal);
 
-    let file = OpenOptions::new()
-        .create(true)
-        .append(true)
-        .open(ELIGIBLE_FILE_PATH)
-        .await
-        .expect("Failed to open file for writing");
-    let eligible_file = Arc::new(Mutex::new(file));
+    let sinks = Arc::new(ResultSinks::open(config).await?);
+    let summary = Arc::new(RunSummary::new(
+        Operation::Check,
+        config.allocation_decimals,
+    ));
 
     let mut join_set = JoinSet::new();
 
-    for (index, secret) in all_wallets.into_iter().enumerate() {
-        let proxies = Arc::clone(&proxies);
-        let eligible_file = Arc::clone(&eligible_file);
+    for (index, wallet) in all_wallets.into_iter().enumerate() {
+        let address = get_address(&wallet);
+
+        let mut events = vec![];
+        for event in &config.allocation_events {
+            if journal.is_done(Operation::Check, event, &address).await {
+                tracing::info!("Wallet {address} was already checked for {event}, skipping");
+            } else {
+                events.push(event.clone());
+            }
+        }
+
+        if events.is_empty() {
+            continue;
+        }
 
-        join_set.spawn(async move {
-            let random_wallet = Keypair::new();
-            let random_address = get_address(&random_wallet);
+        let proxy_pool = Arc::clone(&proxy_pool);
+        let api = Arc::clone(&api);
+        let chain = config.chain.clone();
+        let retry_policy = config.retry_policy.clone();
+        let allocation_decimals = config.allocation_decimals;
+        let sinks = Arc::clone(&sinks);
+        let summary = Arc::clone(&summary);
+        let journal = Arc::clone(&journal);
 
-            let cookie_jar = Arc::new(Jar::default());
-            let proxy = proxies[index % proxies_len].clone();
+        let span = tracing::info_span!(
+            "wallet",
+            index,
+            %address,
+            operation = %Operation::Check,
+            proxy = tracing::field::Empty,
+        );
 
-            if let Err(e) =
-                create_session(&random_wallet, &random_address, Some(&proxy), &cookie_jar).await
-            {
-                tracing::error!("{e}");
-                return;
-            };
-
-            let wallet = match get_wallet(&secret) {
-                Ok(wallet) => wallet,
-                Err(e) => {
-                    tracing::error!("{e}");
-                    return;
+        join_set.spawn(
+            async move {
+                for event in &events {
+                    if let Err(e) = journal
+                        .mark(Operation::Check, event, &address, Status::Pending, None)
+                        .await
+                    {
+                        tracing::error!("Failed to update run journal: {e}");
+                    }
                 }
-            };
-            let address = get_address(&wallet);
-
-            if let Err(e) = points(
-                &wallet,
-                &random_address,
-                &address,
-                Some(&proxy),
-                &cookie_jar,
-                &eligible_file,
-            )
-            .await
-            {
-                tracing::error!("{e}");
-            };
-        });
+
+                let session = Session::new(&proxy_pool, &api, index);
+                let targets: Vec<LinkTarget> = events
+                    .iter()
+                    .map(|event| LinkTarget {
+                        event,
+                        chain: &chain,
+                    })
+                    .collect();
+
+                let results = check_wallet(
+                    &wallet,
+                    &address,
+                    &targets,
+                    &session,
+                    &retry_policy,
+                    allocation_decimals,
+                )
+                .await;
+
+                for result in results {
+                    sinks.write(&result).await;
+
+                    let outcome = result.outcome();
+                    summary
+                        .record(
+                            &address,
+                            outcome.label(),
+                            !result.is_complete(),
+                            result.proxy.as_deref(),
+                        )
+                        .await;
+                    if let Some(allocation) = result.allocation {
+                        summary
+                            .record_allocation(&address, &result.event, allocation)
+                            .await;
+                    }
+
+                    let status = if result.is_complete() {
+                        Status::Done
+                    } else {
+                        Status::Failed
+                    };
+
+                    if let Err(e) = journal
+                        .mark(
+                            Operation::Check,
+                            &result.event,
+                            &address,
+                            status,
+                            result.error,
+                        )
+                        .await
+                    {
+                        tracing::error!("Failed to update run journal: {e}");
+                    }
+                }
+            }
+            .instrument(span),
+        );
 
         if join_set.len() >= config.parallelism {
             if let Some(Err(e)) = join_set.join_next().await {
@@ -92,7 +159,276 @@ pub async fn checker(config: &Config) -> eyre::Result<()> {
         }
     }
 
-    tracing::info!("Finished! Eligible wallets are in data/eligible.txt");
+    proxy_pool.log_summary();
+
+    let report = summary.finish().await;
+    report.log();
+    if let Err(e) = report.write(&config.check_report_file).await {
+        tracing::error!("Failed to write run report: {e}");
+    }
+
+    tracing::info!(
+        "Finished! Eligible wallets are in {}, not eligible in {}, failed in {}",
+        config.eligible_file.display(),
+        config.not_eligible_file.display(),
+        config.failed_file.display()
+    );
 
     Ok(())
 }
+
+async fn check_wallet(
+    wallet: &Keypair,
+    address: &str,
+    targets: &[LinkTarget<'_>],
+    session: &Session<'_>,
+    retry_policy: &RetryPolicy,
+    default_decimals: u32,
+) -> Vec<CheckResult> {
+    let random_wallet = Keypair::new();
+    let random_address = get_address(&random_wallet);
+
+    if let Err(e) = create_session(&random_wallet, &random_address, session, retry_policy).await {
+        tracing::error!("{e}");
+        return targets
+            .iter()
+            .map(|target| {
+                CheckResult::failed(address, target.event, session.last_proxy_label(), &e)
+            })
+            .collect();
+    }
+
+    let mut results = Vec::with_capacity(targets.len());
+
+    for target in targets {
+        let event = target.event;
+
+        let result = match points(
+            wallet,
+            &random_wallet,
+            address,
+            target,
+            session,
+            retry_policy,
+        )
+        .instrument(tracing::info_span!("event", event))
+        .await
+        {
+            Ok(allocation) => CheckResult::checked(
+                address,
+                event,
+                session.last_proxy_label(),
+                allocation,
+                default_decimals,
+            ),
+            Err(e) => {
+                tracing::error!("{e}");
+                CheckResult::failed(address, event, session.last_proxy_label(), &e)
+            }
+        };
+
+        results.push(result);
+    }
+
+    results
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use solana_sdk::signer::Signer;
+
+    use crate::me::mock::MockServer;
+
+    use super::*;
+
+    fn test_config(dir: &Path, server: &MockServer, extra: &str) -> Config {
+        toml::from_str(&format!(
+            r#"
+            {extra}
+            PARALLELISM = 2
+            SECRETS_FILE = "{dir}/secrets.txt"
+            ELIGIBLE_FILE = "{dir}/eligible.txt"
+            NOT_ELIGIBLE_FILE = "{dir}/not_eligible.txt"
+            FAILED_FILE = "{dir}/failed.txt"
+            STATE_FILE = "{dir}/state.json"
+            CHECK_REPORT_FILE = "{dir}/check_report.json"
+            DIRECT_CONNECTION = true
+
+            [retry]
+            max_attempts = 1
+
+            [endpoints]
+            me_api = "{url}"
+            me_foundation = "{url}"
+            "#,
+            dir = dir.display(),
+            url = server.base_url,
+        ))
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn checks_wallets_against_mock_server() {
+        let eligible = Keypair::new();
+        let not_eligible = Keypair::new();
+
+        let server = MockServer::start([(eligible.pubkey().to_string(), 1_500_000)].into()).await;
+        let dir = tempfile::tempdir().unwrap();
+        let config = test_config(dir.path(), &server, "");
+
+        tokio::fs::write(
+            &config.secrets_file,
+            format!(
+                "{}\n{}\n",
+                eligible.to_base58_string(),
+                not_eligible.to_base58_string()
+            ),
+        )
+        .await
+        .unwrap();
+
+        checker(&config).await.unwrap();
+
+        let eligible_output = tokio::fs::read_to_string(&config.eligible_file)
+            .await
+            .unwrap();
+        let not_eligible_output = tokio::fs::read_to_string(&config.not_eligible_file)
+            .await
+            .unwrap();
+        let failed_output = tokio::fs::read_to_string(&config.failed_file)
+            .await
+            .unwrap();
+
+        assert_eq!(eligible_output, format!("{}: 1.5\n", eligible.pubkey()));
+        assert_eq!(
+            not_eligible_output,
+            format!("{}: not_eligible\n", not_eligible.pubkey())
+        );
+        assert!(failed_output.is_empty());
+
+        let report: serde_json::Value = serde_json::from_str(
+            &tokio::fs::read_to_string(&config.check_report_file)
+                .await
+                .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(report["wallets"], 2);
+        assert_eq!(report["outcomes"]["eligible"], 1);
+        assert_eq!(report["outcomes"]["not_eligible"], 1);
+        assert_eq!(report["total_allocation"], "1.5");
+        assert_eq!(
+            report["top_allocations"][0]["address"],
+            eligible.pubkey().to_string()
+        );
+
+        let linked = server.state.linked();
+        assert!(server.state.rejections().is_empty());
+        assert_eq!(linked.len(), 2);
+        assert!(linked
+            .iter()
+            .all(|link| link.allocation_event == "tge-airdrop-final"));
+
+        checker(&config).await.unwrap();
+
+        assert_eq!(server.state.linked().len(), 2);
+        assert_eq!(
+            tokio::fs::read_to_string(&config.eligible_file)
+                .await
+                .unwrap(),
+            eligible_output
+        );
+    }
+
+    #[tokio::test]
+    async fn tags_results_with_their_event() {
+        let wallet = Keypair::new();
+
+        let server = MockServer::start([(wallet.pubkey().to_string(), 2_000_000)].into()).await;
+        let dir = tempfile::tempdir().unwrap();
+        let config = test_config(
+            dir.path(),
+            &server,
+            r#"ALLOCATION_EVENTS = ["tge-airdrop-final", "season-2"]"#,
+        );
+
+        tokio::fs::write(&config.secrets_file, wallet.to_base58_string())
+            .await
+            .unwrap();
+
+        checker(&config).await.unwrap();
+
+        let eligible_output = tokio::fs::read_to_string(&config.eligible_file)
+            .await
+            .unwrap();
+        let mut lines: Vec<&str> = eligible_output.lines().collect();
+        lines.sort();
+
+        assert_eq!(
+            lines,
+            [
+                format!("{} [season-2]: 2", wallet.pubkey()),
+                format!("{} [tge-airdrop-final]: 2", wallet.pubkey()),
+            ]
+        );
+
+        let mut events: Vec<String> = server
+            .state
+            .linked()
+            .into_iter()
+            .map(|link| link.allocation_event)
+            .collect();
+        events.sort();
+
+        assert_eq!(events, ["season-2", "tge-airdrop-final"]);
+
+        let journal = Journal::open(&config.state_file).await.unwrap();
+        let address = wallet.pubkey().to_string();
+        assert!(
+            journal
+                .is_done(Operation::Check, "season-2", &address)
+                .await
+        );
+        assert!(
+            journal
+                .is_done(Operation::Check, "tge-airdrop-final", &address)
+                .await
+        );
+    }
+
+    #[tokio::test]
+    async fn unknown_allocations_are_kept_out_of_eligible_output_until_resolved() {
+        let wallet = Keypair::new();
+
+        let server = MockServer::start([(wallet.pubkey().to_string(), 1_500_000)].into()).await;
+        let dir = tempfile::tempdir().unwrap();
+        let config = test_config(dir.path(), &server, "");
+        let read = |path: &std::path::PathBuf| std::fs::read_to_string(path).unwrap();
+
+        tokio::fs::write(&config.secrets_file, wallet.to_base58_string())
+            .await
+            .unwrap();
+
+        server.state.fail_wallet_pages(true);
+        checker(&config).await.unwrap();
+        checker(&config).await.unwrap();
+
+        assert!(read(&config.eligible_file).is_empty());
+        let failed_output = read(&config.failed_file);
+        assert_eq!(failed_output.lines().count(), 2);
+        assert!(failed_output
+            .lines()
+            .all(|line| line.starts_with(&format!("{}: allocation unknown (", wallet.pubkey()))));
+
+        server.state.fail_wallet_pages(false);
+        checker(&config).await.unwrap();
+        checker(&config).await.unwrap();
+
+        assert_eq!(
+            read(&config.eligible_file),
+            format!("{}: 1.5\n", wallet.pubkey())
+        );
+        assert_eq!(read(&config.failed_file), failed_output);
+    }
+}
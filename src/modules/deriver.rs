@@ -0,0 +1,11 @@
+use crate::{config::Config, crypto::signer::get_address};
+
+use super::load_wallets;
+
+pub async fn deriver(config: &Config) -> eyre::Result<()> {
+    for wallet in load_wallets(&config.secrets_file, config).await? {
+        println!("{}", get_address(&wallet));
+    }
+
+    Ok(())
+}
@@ -1,111 +1,383 @@
-use std::sync::Arc;
+use std::{
+    collections::HashMap,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+};
 
-use reqwest::{cookie::Jar, Proxy};
+use solana_sdk::signature::Keypair;
 use tokio::task::JoinSet;
+use tracing::Instrument;
 
 use crate::{
     config::Config,
-    crypto::signer::{get_address, get_wallet},
+    crypto::{
+        derivation::{AccountRange, DerivationSettings},
+        signer::get_address,
+    },
+    me::{error::ApiError, rsc::RscError},
     utils::{
-        constants::{CLAIM_SECRETS_FILE_PATH, PROXIES_FILE_PATH, SECRETS_FILE_PATH},
-        files::read_file_lines,
+        journal::{Journal, Operation, Status},
+        retry::RetryPolicy,
+        session::Session,
     },
 };
 
-use super::processor::{create_session, link_wallet};
+use super::{
+    load_api_context, load_proxy_pool,
+    processor::{create_session, link_wallet, linked_allocations, LinkTarget},
+    summary::RunSummary,
+    SecretsFile,
+};
+
+struct Progress {
+    journal: Journal,
+    summary: RunSummary,
+    pending_links: HashMap<String, AtomicUsize>,
+}
+
+impl Progress {
+    fn is_last_link(&self, claim_address: &str) -> bool {
+        self.pending_links
+            .get(claim_address)
+            .is_some_and(|pending| pending.fetch_sub(1, Ordering::AcqRel) == 1)
+    }
+
+    async fn mark(
+        &self,
+        event: &str,
+        address: &str,
+        status: Status,
+        error: Option<String>,
+        proxy: Option<&str>,
+    ) {
+        match status {
+            Status::Pending => {}
+            Status::Done => self.summary.record(address, "linked", false, proxy).await,
+            Status::Failed => self.summary.record(address, "failed", true, proxy).await,
+        }
+
+        if let Err(e) = self
+            .journal
+            .mark(Operation::Link, event, address, status, error)
+            .await
+        {
+            tracing::error!("Failed to update run journal: {e}");
+        }
+    }
+}
 
 async fn process_wallet(
-    secret: String,
-    claim_wallets: Arc<Vec<String>>,
-    proxies: Arc<Vec<Proxy>>,
-    proxies_len: usize,
-    index: usize,
-) -> eyre::Result<(), (usize, String)> {
-    let main_wallet = get_wallet(&claim_wallets[index]).expect("Invalid main wallet secret");
-    let main_address = Arc::new(get_address(&main_wallet));
+    wallet: &Keypair,
+    address: &str,
+    main_wallet: &Keypair,
+    targets: &[LinkTarget<'_>],
+    session: &Session<'_>,
+    retry_policy: &RetryPolicy,
+    progress: &Progress,
+) -> bool {
+    let main_address = get_address(main_wallet);
 
-    let cookie_jar = Arc::new(Jar::default());
-    let proxy = proxies[index % proxies_len].clone();
+    if let Err(e) = create_session(main_wallet, &main_address, session, retry_policy).await {
+        tracing::error!("Failed to create session for claim wallet {main_address}: {e}");
+        for target in targets {
+            progress
+                .mark(
+                    target.event,
+                    address,
+                    Status::Failed,
+                    Some(e.to_string()),
+                    session.last_proxy_label(),
+                )
+                .await;
+        }
+        return false;
+    }
 
-    if let Err(e) = create_session(&main_wallet, &main_address, proxies.first(), &cookie_jar).await
-    {
-        tracing::error!("{e}");
-        return Err((index, secret));
-    };
+    for target in targets {
+        let event = target.event;
 
-    let wallet = match get_wallet(&secret) {
-        Ok(wallet) => wallet,
+        match link_wallet(wallet, main_wallet, address, target, session, retry_policy)
+            .instrument(tracing::info_span!("event", event))
+            .await
+        {
+            Ok(_) => {
+                tracing::info!("Wallet {address} linked to {main_address} for {event}");
+                progress
+                    .mark(
+                        event,
+                        address,
+                        Status::Done,
+                        None,
+                        session.last_proxy_label(),
+                    )
+                    .await;
+            }
+            Err(e) => {
+                tracing::error!("Failed to link wallet {address} for {event}: {e}");
+                progress
+                    .mark(
+                        event,
+                        address,
+                        Status::Failed,
+                        Some(e.to_string()),
+                        session.last_proxy_label(),
+                    )
+                    .await;
+            }
+        }
+    }
+
+    true
+}
+
+async fn report_linked_allocations(
+    main_wallet: &Keypair,
+    session: &Session<'_>,
+    retry_policy: &RetryPolicy,
+    default_decimals: u32,
+    summary: &RunSummary,
+) {
+    let main_address = get_address(main_wallet);
+
+    let allocations = match linked_allocations(main_wallet, session, retry_policy).await {
+        Ok(allocations) => allocations,
+        Err(ApiError::Rsc(RscError::NoAllocation)) => {
+            tracing::info!("No allocations for wallets linked to {main_address}");
+            return;
+        }
         Err(e) => {
-            tracing::error!("{e}");
-            return Err((index, secret));
+            tracing::warn!("Failed to fetch allocations for wallets linked to {main_address}: {e}");
+            return;
         }
     };
-    let address = get_address(&wallet);
 
-    if let Err(e) = link_wallet(&wallet, &main_address, &address, Some(&proxy), &cookie_jar).await {
-        tracing::error!("{e}");
-        return Err((index, secret));
-    };
+    for record in &allocations.records {
+        if let (Some(wallet), Some(event)) = (&record.wallet, &record.event) {
+            summary
+                .record_allocation(wallet, event, record.token_amount(default_decimals))
+                .await;
+        }
 
-    tracing::info!("Wallet {address} linked to {main_address}");
+        tracing::info!(
+            "Linked to {main_address}: {} - {} ({}), category: {}, claim status: {}",
+            record.wallet.as_deref().unwrap_or("unknown wallet"),
+            record.token_amount(default_decimals),
+            record.event.as_deref().unwrap_or("unknown event"),
+            record.category.as_deref().unwrap_or("-"),
+            record.claim_status.as_deref().unwrap_or("-"),
+        );
+    }
+}
 
-    Ok(())
+struct WalletPair {
+    wallet: Keypair,
+    claim_wallet: Arc<Keypair>,
 }
 
-async fn process_wallet_with_retries(
-    mut secret: String,
-    claim_wallets: Arc<Vec<String>>,
-    proxies: Arc<Vec<Proxy>>,
-    proxies_len: usize,
+fn pair_line(
+    secrets: &mut SecretsFile,
+    claim_secrets: &mut SecretsFile,
     index: usize,
-) {
-    loop {
-        match process_wallet(
-            secret.clone(),
-            claim_wallets.clone(),
-            proxies.clone(),
-            proxies_len,
-            index,
-        )
-        .await
-        {
-            Ok(()) => break,
-            Err((failed_index, failed_secret)) => {
-                tracing::warn!("Retrying wallet at index {failed_index}");
-                secret = failed_secret;
-                tokio::time::sleep(tokio::time::Duration::from_secs(5)).await;
+) -> eyre::Result<Vec<WalletPair>> {
+    let wallets = secrets
+        .wallets(index)
+        .map_err(|e| eyre::eyre!("{}: {e}", secrets.path.display()))?;
+    let claim_wallets: Vec<Arc<Keypair>> = claim_secrets
+        .wallets(index)
+        .map_err(|e| eyre::eyre!("{}: {e}", claim_secrets.path.display()))?
+        .into_iter()
+        .map(Arc::new)
+        .collect();
+
+    match claim_wallets.as_slice() {
+        [claim_wallet] => Ok(wallets
+            .into_iter()
+            .map(|wallet| WalletPair {
+                wallet,
+                claim_wallet: Arc::clone(claim_wallet),
+            })
+            .collect()),
+        _ if claim_wallets.len() == wallets.len() => Ok(wallets
+            .into_iter()
+            .zip(claim_wallets)
+            .map(|(wallet, claim_wallet)| WalletPair {
+                wallet,
+                claim_wallet,
+            })
+            .collect()),
+        _ => eyre::bail!(
+            "{} airdrop wallets cannot be paired with {} claim wallets, derive one claim wallet or as many as airdrop wallets",
+            wallets.len(),
+            claim_wallets.len()
+        ),
+    }
+}
+
+async fn load_wallet_pairs(config: &Config) -> eyre::Result<Vec<WalletPair>> {
+    let mut secrets =
+        SecretsFile::read(&config.secrets_file, config.derivation.clone(), config).await?;
+
+    // A claim secret stands for a single claim wallet unless its own line asks for a range
+    let claim_settings = DerivationSettings {
+        accounts: AccountRange::default(),
+        ..config.derivation.clone()
+    };
+    let mut claim_secrets =
+        SecretsFile::read(&config.claim_secrets_file, claim_settings, config).await?;
+
+    if secrets.len() != claim_secrets.len() {
+        eyre::bail!(
+            "Number of claim secrets ({}, {}) not equals to airdrop secrets ({}, {})",
+            config.claim_secrets_file.display(),
+            claim_secrets.len(),
+            config.secrets_file.display(),
+            secrets.len()
+        );
+    }
+
+    let mut pairs = vec![];
+    let mut invalid = 0;
+    for index in 0..secrets.len() {
+        match pair_line(&mut secrets, &mut claim_secrets, index) {
+            Ok(line_pairs) => pairs.extend(line_pairs),
+            Err(e) => {
+                invalid += 1;
+                tracing::error!("Secret at index {index}: {e}");
             }
         }
     }
+
+    if invalid > 0 {
+        eyre::bail!("{invalid} secret lines could not be paired, nothing was linked");
+    }
+
+    Ok(pairs)
 }
 
 pub async fn linker(config: &Config) -> eyre::Result<()> {
-    let proxies: Vec<Proxy> = read_file_lines(PROXIES_FILE_PATH)
-        .await?
-        .iter()
-        .map(|proxy_url| Proxy::all(proxy_url).expect("Invalid proxy URL"))
-        .collect();
+    let proxy_pool = Arc::new(load_proxy_pool(config).await?);
+    let api = Arc::new(load_api_context(config).await?);
+
+    let pairs = load_wallet_pairs(config).await?;
 
-    let proxies = Arc::new(proxies);
-    let proxies_len = proxies.len();
+    let journal = Journal::open(&config.state_file).await?;
+    if !config.resume {
+        journal.reset(Operation::Link).await?;
+    }
 
-    let claim_wallets = Arc::new(read_file_lines(CLAIM_SECRETS_FILE_PATH).await?);
-    let all_wallets = read_file_lines(SECRETS_FILE_PATH).await?;
+    let mut jobs = vec![];
+    let mut pending_links: HashMap<String, AtomicUsize> = HashMap::new();
+
+    for (
+        index,
+        WalletPair {
+            wallet,
+            claim_wallet,
+        },
+    ) in pairs.into_iter().enumerate()
+    {
+        let address = get_address(&wallet);
 
-    if claim_wallets.len() != all_wallets.len() {
-        tracing::warn!("Number of claim wallets (claim_secrets.txt) not equals to airdrop wallets (secrets.txt)");
-        return Ok(());
+        let mut events = vec![];
+        for event in &config.allocation_events {
+            if journal.is_done(Operation::Link, event, &address).await {
+                tracing::info!("Wallet {address} was already linked for {event}, skipping");
+            } else {
+                events.push(event.clone());
+            }
+        }
+
+        if events.is_empty() {
+            continue;
+        }
+
+        *pending_links
+            .entry(get_address(&claim_wallet))
+            .or_default()
+            .get_mut() += 1;
+        jobs.push((index, wallet, claim_wallet, address, events));
     }
 
+    let progress = Arc::new(Progress {
+        journal,
+        summary: RunSummary::new(Operation::Link, config.allocation_decimals),
+        pending_links,
+    });
+
     let mut join_set = JoinSet::new();
 
-    for (index, secret) in all_wallets.into_iter().enumerate() {
-        let proxies = Arc::clone(&proxies);
-        let claim_wallets = Arc::clone(&claim_wallets);
+    for (index, wallet, main_wallet, address, events) in jobs {
+        let proxy_pool = Arc::clone(&proxy_pool);
+        let api = Arc::clone(&api);
+        let chain = config.chain.clone();
+        let allocation_decimals = config.allocation_decimals;
+        let progress = Arc::clone(&progress);
+        let retry_policy = config.retry_policy.clone();
+
+        let span = tracing::info_span!(
+            "wallet",
+            index,
+            %address,
+            operation = %Operation::Link,
+            proxy = tracing::field::Empty,
+        );
+
+        join_set.spawn(
+            async move {
+                for event in &events {
+                    progress
+                        .mark(event, &address, Status::Pending, None, None)
+                        .await;
+                }
+
+                let session = Session::new(&proxy_pool, &api, index);
+                let targets: Vec<LinkTarget> = events
+                    .iter()
+                    .map(|event| LinkTarget {
+                        event,
+                        chain: &chain,
+                    })
+                    .collect();
 
-        join_set.spawn(async move {
-            process_wallet_with_retries(secret, claim_wallets, proxies, proxies_len, index).await;
-        });
+                let logged_in = process_wallet(
+                    &wallet,
+                    &address,
+                    &main_wallet,
+                    &targets,
+                    &session,
+                    &retry_policy,
+                    &progress,
+                )
+                .await;
+
+                // Every wallet linked to this claim wallet shows up on one /wallets page,
+                // so it is fetched once by whichever of them finishes last
+                let main_address = get_address(&main_wallet);
+                if progress.is_last_link(&main_address) {
+                    if !logged_in {
+                        if let Err(e) =
+                            create_session(&main_wallet, &main_address, &session, &retry_policy)
+                                .await
+                        {
+                            tracing::warn!("Failed to fetch allocations for wallets linked to {main_address}: {e}");
+                            return;
+                        }
+                    }
+
+                    report_linked_allocations(
+                        &main_wallet,
+                        &session,
+                        &retry_policy,
+                        allocation_decimals,
+                        &progress.summary,
+                    )
+                    .await;
+                }
+            }
+            .instrument(span),
+        );
 
         if join_set.len() >= config.parallelism {
             if let Some(Err(e)) = join_set.join_next().await {
@@ -121,7 +393,375 @@ pub async fn linker(config: &Config) -> eyre::Result<()> {
         }
     }
 
-    tracing::info!("Finished! Successfully linked wallets");
+    proxy_pool.log_summary();
+
+    let report = progress.summary.finish().await;
+    report.log();
+    if let Err(e) = report.write(&config.link_report_file).await {
+        tracing::error!("Failed to write run report: {e}");
+    }
+
+    tracing::info!(
+        "Finished! Linking results are in {}",
+        config.state_file.display()
+    );
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use solana_sdk::signer::Signer;
+
+    use crate::{
+        crypto::signer::get_wallets,
+        me::{
+            context::ApiContext,
+            error::ErrorClass,
+            mock::{LinkedWallet, MockServer},
+        },
+        utils::proxy_pool::ProxyPool,
+    };
+
+    use super::*;
+
+    fn test_config(dir: &Path, server: &MockServer, extra: &str) -> Config {
+        toml::from_str(&format!(
+            r#"
+            PARALLELISM = 1
+            SECRETS_FILE = "{dir}/secrets.txt"
+            CLAIM_SECRETS_FILE = "{dir}/claim_secrets.txt"
+            STATE_FILE = "{dir}/state.json"
+            LINK_REPORT_FILE = "{dir}/link_report.json"
+            DIRECT_CONNECTION = true
+
+            [retry]
+            max_attempts = 1
+
+            [endpoints]
+            me_api = "{url}"
+            me_foundation = "{url}"
+
+            {extra}
+            "#,
+            dir = dir.display(),
+            url = server.base_url,
+        ))
+        .unwrap()
+    }
+
+    fn secrets(keypairs: &[&Keypair]) -> String {
+        keypairs
+            .iter()
+            .map(|keypair| keypair.to_base58_string() + "\n")
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn links_wallets_to_claim_wallets() {
+        let wallets = [Keypair::new(), Keypair::new()];
+        let claim_wallets = [Keypair::new(), Keypair::new()];
+
+        let server = MockServer::start(Default::default()).await;
+        let dir = tempfile::tempdir().unwrap();
+        let config = test_config(dir.path(), &server, "");
+
+        tokio::fs::write(&config.secrets_file, secrets(&[&wallets[0], &wallets[1]]))
+            .await
+            .unwrap();
+        tokio::fs::write(
+            &config.claim_secrets_file,
+            secrets(&[&claim_wallets[0], &claim_wallets[1]]),
+        )
+        .await
+        .unwrap();
+
+        linker(&config).await.unwrap();
+
+        let linked = server.state.linked();
+        let expected: Vec<LinkedWallet> = wallets
+            .iter()
+            .zip(&claim_wallets)
+            .map(|(wallet, claim_wallet)| LinkedWallet {
+                wallet: wallet.pubkey().to_string(),
+                claim_wallet: claim_wallet.pubkey().to_string(),
+                allocation_event: "tge-airdrop-final".to_string(),
+            })
+            .collect();
+
+        assert_eq!(linked, expected);
+
+        let report: serde_json::Value = serde_json::from_str(
+            &tokio::fs::read_to_string(&config.link_report_file)
+                .await
+                .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(report["operation"], "link");
+        assert_eq!(report["outcomes"]["linked"], 2);
+
+        let journal = Journal::open(&config.state_file).await.unwrap();
+        for wallet in &wallets {
+            assert!(
+                journal
+                    .is_done(
+                        Operation::Link,
+                        "tge-airdrop-final",
+                        &wallet.pubkey().to_string()
+                    )
+                    .await
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn one_session_reports_every_wallet_linked_to_a_claim_wallet() {
+        let wallets = [Keypair::new(), Keypair::new()];
+        let claim_wallet = Keypair::new();
+
+        let server = MockServer::start(
+            wallets
+                .iter()
+                .map(|wallet| (wallet.pubkey().to_string(), 3_000_000))
+                .collect(),
+        )
+        .await;
+        let dir = tempfile::tempdir().unwrap();
+        let config = test_config(dir.path(), &server, "");
+
+        tokio::fs::write(&config.secrets_file, secrets(&[&wallets[0], &wallets[1]]))
+            .await
+            .unwrap();
+        tokio::fs::write(
+            &config.claim_secrets_file,
+            secrets(&[&claim_wallet, &claim_wallet]),
+        )
+        .await
+        .unwrap();
+
+        linker(&config).await.unwrap();
+
+        assert_eq!(server.state.wallet_pages(), 1);
+        let report: serde_json::Value = serde_json::from_str(
+            &tokio::fs::read_to_string(&config.link_report_file)
+                .await
+                .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(report["total_allocation"], "6");
+
+        let pool = ProxyPool::direct(config.proxy_pool.clone());
+        let api = ApiContext {
+            endpoints: config.endpoints.clone(),
+            ..Default::default()
+        };
+        let session = Session::new(&pool, &api, 0);
+        let claim_address = get_address(&claim_wallet);
+
+        create_session(
+            &claim_wallet,
+            &claim_address,
+            &session,
+            &config.retry_policy,
+        )
+        .await
+        .unwrap();
+        let allocations = linked_allocations(&claim_wallet, &session, &config.retry_policy)
+            .await
+            .unwrap();
+
+        let mut reported: Vec<String> = allocations
+            .records
+            .iter()
+            .filter_map(|record| record.wallet.clone())
+            .collect();
+        reported.sort();
+        let mut expected: Vec<String> = wallets
+            .iter()
+            .map(|wallet| wallet.pubkey().to_string())
+            .collect();
+        expected.sort();
+
+        assert_eq!(reported, expected);
+        assert!(allocations.records.iter().all(|record| {
+            record.amount == 3_000_000
+                && record.category.as_deref() == Some("trader")
+                && record.claim_status.as_deref() == Some("unclaimed")
+        }));
+    }
+
+    #[tokio::test]
+    async fn reports_the_group_when_its_last_wallet_fails_to_log_in() {
+        let wallets = [Keypair::new(), Keypair::new()];
+        let claim_wallet = Keypair::new();
+
+        let server = MockServer::start(
+            wallets
+                .iter()
+                .map(|wallet| (wallet.pubkey().to_string(), 3_000_000))
+                .collect(),
+        )
+        .await;
+        let dir = tempfile::tempdir().unwrap();
+        let config = test_config(dir.path(), &server, "");
+
+        tokio::fs::write(&config.secrets_file, secrets(&[&wallets[0], &wallets[1]]))
+            .await
+            .unwrap();
+        tokio::fs::write(
+            &config.claim_secrets_file,
+            secrets(&[&claim_wallet, &claim_wallet]),
+        )
+        .await
+        .unwrap();
+
+        server.state.reject_nth_login(2);
+        linker(&config).await.unwrap();
+
+        assert_eq!(server.state.linked().len(), 1);
+        assert_eq!(server.state.wallet_pages(), 1);
+        let report: serde_json::Value = serde_json::from_str(
+            &tokio::fs::read_to_string(&config.link_report_file)
+                .await
+                .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(report["outcomes"]["failed"], 1);
+        assert_eq!(report["total_allocation"], "3");
+    }
+
+    #[tokio::test]
+    async fn logs_in_again_when_the_session_expires() {
+        let wallet = Keypair::new();
+        let claim_wallet = Keypair::new();
+
+        let server = MockServer::start([(wallet.pubkey().to_string(), 1_000_000)].into()).await;
+        let dir = tempfile::tempdir().unwrap();
+        let config = test_config(dir.path(), &server, "");
+
+        let pool = ProxyPool::direct(config.proxy_pool.clone());
+        let api = ApiContext {
+            endpoints: config.endpoints.clone(),
+            ..Default::default()
+        };
+        let session = Session::new(&pool, &api, 0);
+        let target = LinkTarget {
+            event: "tge-airdrop-final",
+            chain: &config.chain,
+        };
+
+        create_session(
+            &claim_wallet,
+            &get_address(&claim_wallet),
+            &session,
+            &config.retry_policy,
+        )
+        .await
+        .unwrap();
+
+        server.state.expire_sessions();
+        link_wallet(
+            &wallet,
+            &claim_wallet,
+            &get_address(&wallet),
+            &target,
+            &session,
+            &config.retry_policy,
+        )
+        .await
+        .unwrap();
+
+        server.state.expire_sessions();
+        let allocations = linked_allocations(&claim_wallet, &session, &config.retry_policy)
+            .await
+            .unwrap();
+        assert_eq!(allocations.records.len(), 1);
+
+        server.state.expire_sessions();
+        let policy = config.retry_policy.without(ErrorClass::Unauthorized);
+        assert!(matches!(
+            linked_allocations(&claim_wallet, &session, &policy).await,
+            Err(ApiError::Unauthorized { .. })
+        ));
+    }
+
+    #[tokio::test]
+    async fn refuses_to_link_when_a_secret_line_fails_to_load() {
+        let wallets = [Keypair::new(), Keypair::new(), Keypair::new()];
+        let claim_wallets = [Keypair::new(), Keypair::new()];
+
+        let server = MockServer::start(Default::default()).await;
+        let dir = tempfile::tempdir().unwrap();
+        let config = test_config(dir.path(), &server, "");
+
+        tokio::fs::write(
+            &config.secrets_file,
+            format!(
+                "{}not a secret\n{}",
+                secrets(&[&wallets[0]]),
+                secrets(&[&wallets[1], &wallets[2]])
+            ),
+        )
+        .await
+        .unwrap();
+        tokio::fs::write(
+            &config.claim_secrets_file,
+            format!(
+                "{}not a secret\n",
+                secrets(&[&claim_wallets[0], &claim_wallets[1], &claim_wallets[0]])
+            ),
+        )
+        .await
+        .unwrap();
+
+        assert!(linker(&config).await.is_err());
+        assert!(server.state.linked().is_empty());
+    }
+
+    #[tokio::test]
+    async fn pairs_secret_lines_before_deriving_accounts() {
+        const MNEMONIC: &str = "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about";
+        const CLAIM_MNEMONIC: &str =
+            "legal winner thank year wave sausage worth useful legal winner thank yellow";
+
+        let server = MockServer::start(Default::default()).await;
+        let dir = tempfile::tempdir().unwrap();
+        let config = test_config(dir.path(), &server, "[derivation]\naccounts = \"0..2\"");
+
+        tokio::fs::write(&config.secrets_file, MNEMONIC)
+            .await
+            .unwrap();
+        tokio::fs::write(&config.claim_secrets_file, CLAIM_MNEMONIC)
+            .await
+            .unwrap();
+
+        linker(&config).await.unwrap();
+
+        let addresses = |secret: &str, settings: &DerivationSettings| -> Vec<String> {
+            get_wallets(secret, settings, |_| Ok(Default::default()))
+                .unwrap()
+                .iter()
+                .map(get_address)
+                .collect()
+        };
+        let wallets = addresses(MNEMONIC, &config.derivation);
+        let claim_wallet = addresses(CLAIM_MNEMONIC, &DerivationSettings::default()).remove(0);
+        assert_eq!(wallets.len(), 2);
+
+        let mut linked = server.state.linked();
+        linked.sort_by(|a, b| a.wallet.cmp(&b.wallet));
+        let mut expected: Vec<LinkedWallet> = wallets
+            .into_iter()
+            .map(|wallet| LinkedWallet {
+                wallet,
+                claim_wallet: claim_wallet.clone(),
+                allocation_event: "tge-airdrop-final".to_string(),
+            })
+            .collect();
+        expected.sort_by(|a, b| a.wallet.cmp(&b.wallet));
+
+        assert_eq!(linked, expected);
+    }
+}
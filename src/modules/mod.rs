@@ -1,16 +1,209 @@
 mod checker;
+mod deriver;
 mod linker;
 mod processor;
+mod results;
+mod summary;
+mod vault;
 
-use crate::config::Config;
+use std::path::Path;
+
+use solana_sdk::signature::Keypair;
+
+use crate::{
+    cli::{Cli, Command},
+    config::Config,
+    crypto::{
+        derivation::{DerivationSettings, PassphraseSource},
+        signer::get_wallets,
+        vault::Vault,
+    },
+    me::{context::ApiContext, headers::HeaderProfiles},
+    utils::{files::secret_lines, proxy_pool::ProxyPool},
+};
 
 use checker::checker;
-use dialoguer::{theme::ColorfulTheme, Select};
+use deriver::deriver;
+use dialoguer::{theme::ColorfulTheme, Password, Select};
 use linker::linker;
+use vault::vault;
+use zeroize::Zeroizing;
+
+pub async fn run(cli: Cli) -> eyre::Result<()> {
+    let mut config = Config::read_from_file(&cli.config).await?;
+
+    match cli.command {
+        Some(Command::Check(args)) => {
+            args.apply(&mut config);
+            checker(&config).await
+        }
+        Some(Command::Link(args)) => {
+            args.apply(&mut config);
+            linker(&config).await
+        }
+        Some(Command::Derive(args)) => {
+            args.apply(&mut config);
+            deriver(&config).await
+        }
+        Some(Command::Vault(args)) => {
+            let command = args.apply(&mut config);
+            vault(&config, command).await
+        }
+        None => menu(&config).await,
+    }
+}
+
+async fn load_proxy_pool(config: &Config) -> eyre::Result<ProxyPool> {
+    let proxy_pool = if config.direct_connection {
+        ProxyPool::direct(config.proxy_pool.clone())
+    } else {
+        ProxyPool::load(&config.proxies_file, config.proxy_pool.clone()).await?
+    };
+
+    if proxy_pool.is_empty() {
+        tracing::warn!("No proxies configured, using a direct connection");
+    }
+
+    Ok(proxy_pool.with_rate_limit(config.rate_limit))
+}
+
+async fn read_secrets(
+    path: &Path,
+    vault_password: &PassphraseSource,
+) -> eyre::Result<Vec<Zeroizing<String>>> {
+    let contents = Zeroizing::new(tokio::fs::read_to_string(path).await?);
+
+    let Some(vault) = Vault::parse(&contents)? else {
+        return Ok(secret_lines(&contents));
+    };
+
+    let password = read_vault_password(
+        vault_password,
+        &format!("Password for vault {}", path.display()),
+        false,
+    )?;
+
+    Ok(secret_lines(&vault.open(&password)?))
+}
+
+struct SecretsFile<'a> {
+    path: &'a Path,
+    settings: DerivationSettings,
+    secrets: Vec<Zeroizing<String>>,
+    global_passphrase: Option<Zeroizing<String>>,
+}
+
+impl<'a> SecretsFile<'a> {
+    async fn read(
+        path: &'a Path,
+        settings: DerivationSettings,
+        config: &Config,
+    ) -> eyre::Result<Self> {
+        Ok(Self {
+            path,
+            settings,
+            secrets: read_secrets(path, &config.vault_password).await?,
+            global_passphrase: None,
+        })
+    }
+
+    fn len(&self) -> usize {
+        self.secrets.len()
+    }
+
+    fn wallets(&mut self, index: usize) -> eyre::Result<Vec<Keypair>> {
+        let Self {
+            path,
+            settings,
+            secrets,
+            global_passphrase,
+        } = self;
+
+        let passphrase = |source: Option<&PassphraseSource>| match source {
+            Some(source) => read_passphrase(
+                source,
+                &format!("BIP39 passphrase for secret {index} of {}", path.display()),
+            ),
+            None => match global_passphrase {
+                Some(passphrase) => Ok(passphrase.clone()),
+                None => {
+                    let passphrase = read_passphrase(
+                        &settings.passphrase,
+                        &format!("BIP39 passphrase for {}", path.display()),
+                    )?;
+                    *global_passphrase = Some(passphrase.clone());
+                    Ok(passphrase)
+                }
+            },
+        };
+
+        get_wallets(&secrets[index], settings, passphrase)
+    }
+}
+
+async fn load_wallets(path: impl AsRef<Path>, config: &Config) -> eyre::Result<Vec<Keypair>> {
+    let mut secrets = SecretsFile::read(path.as_ref(), config.derivation.clone(), config).await?;
+
+    let mut wallets = vec![];
+    for index in 0..secrets.len() {
+        match secrets.wallets(index) {
+            Ok(derived) => wallets.extend(derived),
+            Err(e) => tracing::error!("Secret at index {index}: {e}"),
+        }
+    }
+
+    Ok(wallets)
+}
+
+fn read_env(name: &str, secret: &str) -> eyre::Result<Zeroizing<String>> {
+    std::env::var(name)
+        .map(Zeroizing::new)
+        .map_err(|_| eyre::eyre!("Environment variable {name} with the {secret} is not set"))
+}
+
+fn read_passphrase(source: &PassphraseSource, prompt: &str) -> eyre::Result<Zeroizing<String>> {
+    match source {
+        PassphraseSource::None => Ok(Zeroizing::default()),
+        PassphraseSource::Env(name) => read_env(name, "BIP39 passphrase"),
+        PassphraseSource::Prompt => Ok(Zeroizing::new(
+            Password::with_theme(&ColorfulTheme::default())
+                .with_prompt(prompt)
+                .allow_empty_password(true)
+                .interact()?,
+        )),
+    }
+}
+
+fn read_vault_password(
+    source: &PassphraseSource,
+    prompt: &str,
+    confirm: bool,
+) -> eyre::Result<Zeroizing<String>> {
+    match source {
+        PassphraseSource::None => {
+            eyre::bail!("Vault password must be read from prompt or env:VARIABLE")
+        }
+        PassphraseSource::Env(name) => read_env(name, "vault password"),
+        PassphraseSource::Prompt => {
+            let theme = ColorfulTheme::default();
+            let mut password = Password::with_theme(&theme).with_prompt(prompt);
+            if confirm {
+                password = password.with_confirmation("Repeat password", "Passwords do not match");
+            }
 
-pub async fn menu() -> eyre::Result<()> {
-    let config = Config::read_default().await;
+            Ok(Zeroizing::new(password.interact()?))
+        }
+    }
+}
+
+async fn load_api_context(config: &Config) -> eyre::Result<ApiContext> {
+    Ok(ApiContext {
+        endpoints: config.endpoints.clone(),
+        headers: HeaderProfiles::load(&config.headers_file).await?,
+    })
+}
 
+pub async fn menu(config: &Config) -> eyre::Result<()> {
     loop {
         let options = vec!["Check allocation", "Link wallets for claim", "Exit"];
 
@@ -22,8 +215,8 @@ pub async fn menu() -> eyre::Result<()> {
             .unwrap();
 
         match selection {
-            0 => checker(&config).await?,
-            1 => linker(&config).await?,
+            0 => checker(config).await?,
+            1 => linker(config).await?,
             2 => {
                 return Ok(());
             }
@@ -31,3 +224,38 @@ pub async fn menu() -> eyre::Result<()> {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use solana_sdk::signer::Signer;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn loads_wallets_from_a_vault() {
+        let keypair = Keypair::new();
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("secrets.vault");
+
+        Vault::seal(&keypair.to_base58_string(), "vault password")
+            .unwrap()
+            .write(&path)
+            .await
+            .unwrap();
+        std::env::set_var("ME_CHECKER_TEST_VAULT_PASSWORD", "vault password");
+
+        let mut config: Config = toml::from_str(
+            "PARALLELISM = 1\nVAULT_PASSWORD = \"env:ME_CHECKER_TEST_VAULT_PASSWORD\"",
+        )
+        .unwrap();
+
+        let wallets = load_wallets(&path, &config).await.unwrap();
+        assert_eq!(
+            wallets.iter().map(Keypair::pubkey).collect::<Vec<_>>(),
+            [keypair.pubkey()]
+        );
+
+        config.vault_password = PassphraseSource::None;
+        assert!(load_wallets(&path, &config).await.is_err());
+    }
+}
@@ -1,38 +1,49 @@
-use std::sync::Arc;
+use std::future::Future;
 
-use reqwest::{cookie::Jar, Proxy};
 use solana_sdk::signature::Keypair;
-use tokio::{fs::File, io::AsyncWriteExt, sync::Mutex};
 use uuid::Uuid;
 
 use crate::{
     crypto::{
         messages::{get_link_wallet_message, get_verify_message},
-        signer::sign_message,
+        signer::{get_address, sign_message},
     },
     me::{
         api::{auth_link_wallet, auth_session, verify_and_create_session, wallets},
+        context::ApiContext,
+        error::{ApiError, ErrorClass},
+        rsc::{parse_allocations, AllocationRecord, Allocations},
         schemas::LinkWalletResponse,
-        utils::extract_allocation_amount,
     },
+    utils::{fetch::HttpClient, retry::RetryPolicy, session::Session},
 };
 
 pub async fn create_session(
     signer: &Keypair,
     signer_address: &str,
-    proxy: Option<&Proxy>,
-    cookie_jar: &Arc<Jar>,
-) -> eyre::Result<()> {
+    session: &Session<'_>,
+    retry_policy: &RetryPolicy,
+) -> Result<(), ApiError> {
+    retry_policy
+        .retry("Create session", |_| {
+            session.call(|client| async move {
+                try_create_session(signer, signer_address, session.api(), &client).await
+            })
+        })
+        .await
+}
+
+async fn try_create_session(
+    signer: &Keypair,
+    signer_address: &str,
+    api: &ApiContext,
+    client: &HttpClient,
+) -> Result<(), ApiError> {
     let uuid = Uuid::new_v4().to_string();
 
-    if auth_session(&uuid, proxy, Some(cookie_jar.clone()))
-        .await
-        .is_err()
-    {
-        eyre::bail!("Auth session failed");
-    }
+    auth_session(&uuid, api, client).await?;
 
-    let verify_message = get_verify_message(&uuid);
+    let verify_message = get_verify_message(&api.endpoints.me_foundation_host(), &uuid);
 
     let verify_signature =
         sign_message(signer, &verify_message).expect("Failed to sign verify message");
@@ -41,40 +52,106 @@ pub async fn create_session(
         signer_address,
         &verify_signature,
         &verify_message,
-        proxy,
-        Some(cookie_jar.clone()),
+        api,
+        client,
     )
-    .await
+    .await?
     {
-        Ok(verify_and_create_response) => match verify_and_create_response {
-            Some(response) => {
-                if !response.success {
-                    eyre::bail!("Verify and create session is not successful");
-                }
+        Some(response) => {
+            if !response.success {
+                return Err(ApiError::SignatureRejected(
+                    "Verify and create session is not successful".to_string(),
+                ));
             }
-            None => eyre::bail!("Verify and create session failed"),
-        },
-        Err(e) => eyre::bail!("Verify and create session failed: {e}"),
+        }
+        None => {
+            return Err(ApiError::UnexpectedResponse(
+                "Empty verify and create session response".to_string(),
+            ))
+        }
     }
 
-    if auth_session(&uuid, proxy, Some(cookie_jar.clone()))
-        .await
-        .is_err()
-    {
-        eyre::bail!("Second auth session failed");
-    }
+    auth_session(&uuid, api, client).await?;
 
     Ok(())
 }
 
+async fn retry_signed_in<T, F, Fut>(
+    operation: &str,
+    claim_wallet: &Keypair,
+    session: &Session<'_>,
+    retry_policy: &RetryPolicy,
+    mut f: F,
+) -> Result<T, ApiError>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<T, ApiError>>,
+{
+    // Retrying with the expired session cookie only burns attempts, log in again instead
+    let policy = retry_policy.without(ErrorClass::Unauthorized);
+
+    match policy.retry(operation, |_| f()).await {
+        Err(e) if retry_policy.is_retryable(&e) && e.class() == ErrorClass::Unauthorized => {
+            tracing::warn!("{operation} failed: {e}. Logging in again");
+            create_session(
+                claim_wallet,
+                &get_address(claim_wallet),
+                session,
+                retry_policy,
+            )
+            .await?;
+
+            policy.retry(operation, |_| f()).await
+        }
+        result => result,
+    }
+}
+
+pub struct LinkTarget<'a> {
+    pub event: &'a str,
+    pub chain: &'a str,
+}
+
 pub async fn link_wallet(
+    target_wallet: &Keypair,
+    claim_wallet: &Keypair,
+    target_address: &str,
+    target: &LinkTarget<'_>,
+    session: &Session<'_>,
+    retry_policy: &RetryPolicy,
+) -> Result<Option<LinkWalletResponse>, ApiError> {
+    let claim_address = &get_address(claim_wallet);
+
+    retry_signed_in("Link wallet", claim_wallet, session, retry_policy, || {
+        session.call(|client| async move {
+            try_link_wallet(
+                target_wallet,
+                claim_address,
+                target_address,
+                target,
+                session.api(),
+                &client,
+            )
+            .await
+        })
+    })
+    .await
+}
+
+async fn try_link_wallet(
     target_wallet: &Keypair,
     claim_address: &str,
     target_address: &str,
-    proxy: Option<&Proxy>,
-    cookie_jar: &Arc<Jar>,
-) -> eyre::Result<Option<LinkWalletResponse>> {
-    let link_message = get_link_wallet_message(claim_address, target_address);
+    target: &LinkTarget<'_>,
+    api: &ApiContext,
+    client: &HttpClient,
+) -> Result<Option<LinkWalletResponse>, ApiError> {
+    let link_message = get_link_wallet_message(
+        &api.endpoints.me_foundation_host(),
+        claim_address,
+        target_address,
+        target.chain,
+    );
 
     let signature =
         sign_message(target_wallet, &link_message).expect("Failed to sign link message");
@@ -83,71 +160,98 @@ pub async fn link_wallet(
         &link_message,
         target_address,
         &signature,
-        proxy,
-        Some(Arc::clone(cookie_jar)),
+        target.chain,
+        target.event,
+        api,
+        client,
     )
     .await
 }
 
+pub struct Allocation {
+    pub eligibility: String,
+    pub record: Option<AllocationRecord>,
+    pub error: Option<String>,
+}
+
+pub async fn linked_allocations(
+    claim_wallet: &Keypair,
+    session: &Session<'_>,
+    retry_policy: &RetryPolicy,
+) -> Result<Allocations, ApiError> {
+    let response = retry_signed_in("Wallets", claim_wallet, session, retry_policy, || {
+        session.call(|client| async move { wallets(session.api(), &client).await })
+    })
+    .await?;
+
+    let Some(response) = response else {
+        return Err(ApiError::UnexpectedResponse(
+            "Empty wallets response".to_string(),
+        ));
+    };
+
+    Ok(parse_allocations(&response)?)
+}
+
 pub async fn points(
     target_wallet: &Keypair,
-    claim_address: &str,
+    claim_wallet: &Keypair,
     target_address: &str,
-    proxy: Option<&Proxy>,
-    cookie_jar: &Arc<Jar>,
-    eligible_file: &Arc<Mutex<File>>,
-) -> eyre::Result<()> {
-    if let Ok(Some(response_items)) = link_wallet(
+    target: &LinkTarget<'_>,
+    session: &Session<'_>,
+    retry_policy: &RetryPolicy,
+) -> Result<Allocation, ApiError> {
+    let response = link_wallet(
         target_wallet,
-        claim_address,
+        claim_wallet,
         target_address,
-        proxy,
-        cookie_jar,
+        target,
+        session,
+        retry_policy,
     )
-    .await
-    {
-        if let Some(response_item) = response_items.first().and_then(|item| item.as_ref()) {
-            if let Some(result) = &response_item.result {
-                if let Some(data) = &result.data {
-                    if let Some(json) = &data.json {
-                        if let Some(eligibility) = &json.eligibility {
-                            if let Some(eligible) = &eligibility.eligibility {
-                                if eligible == "eligible" {
-                                    let wallets_result =
-                                        wallets(proxy, Some(Arc::clone(cookie_jar))).await;
-
-                                    let entry: String = if let Ok(Some(allocation_response)) =
-                                        wallets_result
-                                    {
-                                        if let Some(amount) =
-                                            extract_allocation_amount(&allocation_response)
-                                        {
-                                            if amount == 0 {
-                                                format!("{}\n", target_address)
-                                            } else {
-                                                let allocation: f64 = amount as f64 / 10f64.powi(6);
-
-                                                format!("{}: {}\n", target_address, allocation)
-                                            }
-                                        } else {
-                                            format!("{}\n", target_address)
-                                        }
-                                    } else {
-                                        format!("{}\n", target_address)
-                                    };
-
-                                    let mut f = eligible_file.lock().await;
-                                    if let Err(e) = f.write_all(entry.as_bytes()).await {
-                                        tracing::error!("Failed to write to file: {}", e);
-                                    }
-                                }
-                            }
-                        }
-                    }
-                }
-            }
-        }
+    .await?;
+
+    let Some(eligibility) = response
+        .and_then(|response_items| response_items.into_iter().next().flatten())
+        .and_then(|response_item| response_item.result)
+        .and_then(|result| result.data)
+        .and_then(|data| data.json)
+        .and_then(|json| json.eligibility)
+        .and_then(|eligibility| eligibility.eligibility)
+    else {
+        return Err(ApiError::UnexpectedResponse(
+            "Link wallet response has no eligibility".to_string(),
+        ));
+    };
+
+    if eligibility != "eligible" {
+        return Ok(Allocation {
+            eligibility,
+            record: None,
+            error: None,
+        });
     }
 
-    Ok(())
+    let record = linked_allocations(claim_wallet, session, retry_policy)
+        .await
+        .map_err(|e| format!("Failed to fetch allocation: {e}"))
+        .and_then(|allocations| {
+            allocations
+                .for_wallet(target_address, target.event)
+                .cloned()
+                .ok_or_else(|| format!("Allocation for {target_address} not found in response"))
+        });
+
+    Ok(match record {
+        Ok(record) => Allocation {
+            eligibility,
+            record: Some(record),
+            error: None,
+        },
+        Err(error) => Allocation {
+            eligibility,
+            record: None,
+            error: Some(error),
+        },
+    })
 }
@@ -0,0 +1,361 @@
+use std::path::Path;
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use tokio::{
+    fs::{File, OpenOptions},
+    io::AsyncWriteExt,
+    sync::Mutex,
+};
+
+use crate::{
+    config::{Config, OutputFormat},
+    me::{amount::TokenAmount, error::ApiError},
+};
+
+use super::processor::Allocation;
+
+#[derive(Serialize, Clone, Debug)]
+pub struct CheckResult {
+    pub address: String,
+    pub event: String,
+    pub eligibility: Option<String>,
+    pub allocation_raw: Option<u128>,
+    pub allocation: Option<TokenAmount>,
+    pub category: Option<String>,
+    pub claim_status: Option<String>,
+    pub proxy: Option<String>,
+    pub timestamp: DateTime<Utc>,
+    pub error: Option<String>,
+}
+
+impl CheckResult {
+    pub fn checked(
+        address: &str,
+        event: &str,
+        proxy: Option<&str>,
+        allocation: Allocation,
+        default_decimals: u32,
+    ) -> Self {
+        Self {
+            address: address.to_string(),
+            event: event.to_string(),
+            eligibility: Some(allocation.eligibility),
+            allocation_raw: allocation.record.as_ref().map(|record| record.amount),
+            allocation: allocation
+                .record
+                .as_ref()
+                .map(|record| record.token_amount(default_decimals)),
+            category: allocation
+                .record
+                .as_ref()
+                .and_then(|record| record.category.clone()),
+            claim_status: allocation
+                .record
+                .as_ref()
+                .and_then(|record| record.claim_status.clone()),
+            proxy: proxy.map(str::to_string),
+            timestamp: Utc::now(),
+            error: allocation.error,
+        }
+    }
+
+    pub fn failed(address: &str, event: &str, proxy: Option<&str>, error: &ApiError) -> Self {
+        Self {
+            address: address.to_string(),
+            event: event.to_string(),
+            eligibility: None,
+            allocation_raw: None,
+            allocation: None,
+            category: None,
+            claim_status: None,
+            proxy: proxy.map(str::to_string),
+            timestamp: Utc::now(),
+            error: Some(error.to_string()),
+        }
+    }
+
+    pub fn outcome(&self) -> Outcome {
+        match self.eligibility.as_deref() {
+            Some("eligible") => Outcome::Eligible,
+            Some(_) => Outcome::NotEligible,
+            None => Outcome::Failed,
+        }
+    }
+
+    pub fn is_complete(&self) -> bool {
+        self.outcome() != Outcome::Failed && self.error.is_none()
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Outcome {
+    Eligible,
+    NotEligible,
+    Failed,
+}
+
+impl Outcome {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Outcome::Eligible => "eligible",
+            Outcome::NotEligible => "not_eligible",
+            Outcome::Failed => "failed",
+        }
+    }
+}
+
+const MAX_REASON_LENGTH: usize = 200;
+
+fn single_line(reason: &str) -> String {
+    let flat = reason.split_whitespace().collect::<Vec<_>>().join(" ");
+
+    match flat.char_indices().nth(MAX_REASON_LENGTH) {
+        Some((end, _)) => format!("{}...", &flat[..end]),
+        None => flat,
+    }
+}
+
+pub trait ResultWriter: Send + Sync {
+    fn header(&self) -> Option<String> {
+        None
+    }
+
+    fn format(&self, result: &CheckResult) -> eyre::Result<String>;
+}
+
+pub struct TextWriter {
+    tag_event: bool,
+}
+
+impl ResultWriter for TextWriter {
+    fn format(&self, result: &CheckResult) -> eyre::Result<String> {
+        let wallet = if self.tag_event {
+            format!("{} [{}]", result.address, result.event)
+        } else {
+            result.address.clone()
+        };
+
+        let entry = match result.outcome() {
+            Outcome::Eligible => match (result.allocation, result.error.as_deref()) {
+                (_, Some(error)) => {
+                    format!("{wallet}: allocation unknown ({})\n", single_line(error))
+                }
+                (Some(allocation), None) if !allocation.is_zero() => {
+                    format!("{wallet}: {allocation}\n")
+                }
+                _ => format!("{wallet}\n"),
+            },
+            Outcome::NotEligible => format!(
+                "{wallet}: {}\n",
+                result.eligibility.as_deref().unwrap_or_default()
+            ),
+            Outcome::Failed => format!(
+                "{wallet}: {}\n",
+                single_line(result.error.as_deref().unwrap_or("unknown error"))
+            ),
+        };
+
+        Ok(entry)
+    }
+}
+
+pub struct JsonLinesWriter;
+
+impl ResultWriter for JsonLinesWriter {
+    fn format(&self, result: &CheckResult) -> eyre::Result<String> {
+        Ok(format!("{}\n", serde_json::to_string(result)?))
+    }
+}
+
+pub struct CsvWriter;
+
+impl CsvWriter {
+    const COLUMNS: [&'static str; 10] = [
+        "address",
+        "event",
+        "eligibility",
+        "allocation_raw",
+        "allocation",
+        "category",
+        "claim_status",
+        "proxy",
+        "timestamp",
+        "error",
+    ];
+}
+
+impl ResultWriter for CsvWriter {
+    fn header(&self) -> Option<String> {
+        Some(format!("{}\n", Self::COLUMNS.join(",")))
+    }
+
+    fn format(&self, result: &CheckResult) -> eyre::Result<String> {
+        let mut writer = csv::WriterBuilder::new()
+            .has_headers(false)
+            .from_writer(vec![]);
+        writer.serialize(result)?;
+
+        Ok(String::from_utf8(writer.into_inner()?)?)
+    }
+}
+
+impl OutputFormat {
+    pub fn writer(&self, tag_event: bool) -> Box<dyn ResultWriter> {
+        match self {
+            OutputFormat::Text => Box::new(TextWriter { tag_event }),
+            OutputFormat::Jsonl => Box::new(JsonLinesWriter),
+            OutputFormat::Csv => Box::new(CsvWriter),
+        }
+    }
+}
+
+pub struct ResultSink {
+    file: Mutex<File>,
+    writer: Box<dyn ResultWriter>,
+}
+
+impl ResultSink {
+    pub async fn open(
+        path: impl AsRef<Path>,
+        format: OutputFormat,
+        tag_event: bool,
+    ) -> eyre::Result<Self> {
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .await?;
+
+        let writer = format.writer(tag_event);
+
+        if file.metadata().await?.len() == 0 {
+            if let Some(header) = writer.header() {
+                file.write_all(header.as_bytes()).await?;
+            }
+        }
+
+        Ok(Self {
+            file: Mutex::new(file),
+            writer,
+        })
+    }
+
+    pub async fn write(&self, result: &CheckResult) {
+        let entry = match self.writer.format(result) {
+            Ok(entry) => entry,
+            Err(e) => {
+                tracing::error!("Failed to format result for {}: {e}", result.address);
+                return;
+            }
+        };
+
+        let mut f = self.file.lock().await;
+        if let Err(e) = f.write_all(entry.as_bytes()).await {
+            tracing::error!("Failed to write to file: {}", e);
+        }
+    }
+}
+
+pub struct ResultSinks {
+    eligible: ResultSink,
+    not_eligible: ResultSink,
+    failed: ResultSink,
+}
+
+impl ResultSinks {
+    pub async fn open(config: &Config) -> eyre::Result<Self> {
+        let format = config.output_format;
+        let tag_event = config.allocation_events.len() > 1;
+
+        Ok(Self {
+            eligible: ResultSink::open(&config.eligible_file, format, tag_event).await?,
+            not_eligible: ResultSink::open(&config.not_eligible_file, format, tag_event).await?,
+            failed: ResultSink::open(&config.failed_file, format, tag_event).await?,
+        })
+    }
+
+    pub async fn write(&self, result: &CheckResult) {
+        let sink = match result.outcome() {
+            Outcome::Eligible if result.is_complete() => &self.eligible,
+            Outcome::NotEligible => &self.not_eligible,
+            Outcome::Eligible | Outcome::Failed => &self.failed,
+        };
+
+        sink.write(result).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::me::rsc::AllocationRecord;
+
+    use super::*;
+
+    fn eligible(amount: u128, decimals: Option<u32>) -> CheckResult {
+        CheckResult::checked(
+            "wallet",
+            "tge-airdrop-final",
+            None,
+            Allocation {
+                eligibility: "eligible".to_string(),
+                record: Some(AllocationRecord {
+                    wallet: None,
+                    event: None,
+                    amount,
+                    decimals,
+                    category: None,
+                    claim_status: None,
+                }),
+                error: None,
+            },
+            6,
+        )
+    }
+
+    #[test]
+    fn structured_output_keeps_amounts_exact() {
+        let result = eligible(123_456_789_012_345_678_901, Some(9));
+
+        let csv = CsvWriter.format(&result).unwrap();
+        assert!(csv.contains(",123456789012345678901,123456789012.345678901,"));
+
+        let json = JsonLinesWriter.format(&result).unwrap();
+        assert!(json.contains("\"allocation\":\"123456789012.345678901\""));
+
+        let text = TextWriter { tag_event: false }
+            .format(&eligible(1_500_000, None))
+            .unwrap();
+        assert_eq!(text, "wallet: 1.5\n");
+    }
+
+    #[test]
+    fn eligible_without_allocation_is_not_complete() {
+        let writer = TextWriter { tag_event: false };
+
+        let mut result = eligible(0, None);
+        result.allocation = None;
+        result.error = Some("Failed to fetch allocation: timeout".to_string());
+        assert!(!result.is_complete());
+        assert_eq!(
+            writer.format(&result).unwrap(),
+            "wallet: allocation unknown (Failed to fetch allocation: timeout)\n"
+        );
+    }
+
+    #[test]
+    fn text_failures_stay_on_one_line() {
+        let error = ApiError::from_status(
+            reqwest::StatusCode::BAD_GATEWAY,
+            format!("<html>\n  <body>\n{}\n</body>\n</html>", "x".repeat(500)),
+        );
+
+        let text = TextWriter { tag_event: false }
+            .format(&CheckResult::failed("wallet", "event", None, &error))
+            .unwrap();
+
+        assert!(text.starts_with("wallet: HTTP error: 502 Bad Gateway - <html> <body> xxx"));
+        assert!(text.ends_with("...\n"));
+        assert_eq!(text.lines().count(), 1);
+    }
+}
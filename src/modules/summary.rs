@@ -0,0 +1,238 @@
+use std::{
+    collections::{BTreeMap, HashSet},
+    path::Path,
+    time::{Duration, Instant},
+};
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use tokio::sync::Mutex;
+
+use crate::{me::amount::TokenAmount, utils::journal::Operation};
+
+const TOP_ALLOCATIONS: usize = 10;
+const DIRECT_CONNECTION: &str = "direct";
+
+#[derive(Serialize, Clone, Debug, PartialEq, Eq)]
+pub struct WalletAllocation {
+    pub address: String,
+    pub event: String,
+    pub allocation: TokenAmount,
+}
+
+#[derive(Default)]
+struct Tally {
+    wallets: HashSet<String>,
+    outcomes: BTreeMap<&'static str, usize>,
+    allocations: BTreeMap<(String, String), TokenAmount>,
+    proxy_errors: BTreeMap<String, usize>,
+}
+
+pub struct RunSummary {
+    operation: Operation,
+    started_at: DateTime<Utc>,
+    started: Instant,
+    default_decimals: u32,
+    tally: Mutex<Tally>,
+}
+
+impl RunSummary {
+    pub fn new(operation: Operation, default_decimals: u32) -> Self {
+        Self {
+            operation,
+            started_at: Utc::now(),
+            started: Instant::now(),
+            default_decimals,
+            tally: Mutex::new(Tally::default()),
+        }
+    }
+
+    pub async fn record(
+        &self,
+        address: &str,
+        outcome: &'static str,
+        failed: bool,
+        proxy: Option<&str>,
+    ) {
+        let mut tally = self.tally.lock().await;
+
+        tally.wallets.insert(address.to_string());
+        *tally.outcomes.entry(outcome).or_default() += 1;
+
+        if failed {
+            let proxy = proxy.unwrap_or(DIRECT_CONNECTION).to_string();
+            *tally.proxy_errors.entry(proxy).or_default() += 1;
+        }
+    }
+
+    pub async fn record_allocation(&self, address: &str, event: &str, allocation: TokenAmount) {
+        if allocation.is_zero() {
+            return;
+        }
+
+        self.tally
+            .lock()
+            .await
+            .allocations
+            .insert((address.to_string(), event.to_string()), allocation);
+    }
+
+    pub async fn finish(&self) -> Report {
+        let tally = self.tally.lock().await;
+
+        let mut allocations: Vec<WalletAllocation> = tally
+            .allocations
+            .iter()
+            .map(|((address, event), allocation)| WalletAllocation {
+                address: address.clone(),
+                event: event.clone(),
+                allocation: *allocation,
+            })
+            .collect();
+
+        let mut total_allocation = TokenAmount::zero(self.default_decimals);
+        for entry in &allocations {
+            match total_allocation.checked_add(&entry.allocation) {
+                Some(sum) => total_allocation = sum,
+                None => tracing::warn!(
+                    "Allocation of {} does not fit into the run total",
+                    entry.address
+                ),
+            }
+        }
+
+        allocations.sort_by(|a, b| b.allocation.cmp_amount(&a.allocation));
+        let top_allocations = allocations.iter().take(TOP_ALLOCATIONS).cloned().collect();
+
+        let elapsed = self.started.elapsed();
+
+        Report {
+            operation: self.operation,
+            started_at: self.started_at,
+            finished_at: Utc::now(),
+            elapsed_secs: elapsed.as_secs_f64(),
+            elapsed,
+            wallets: tally.wallets.len(),
+            outcomes: tally.outcomes.clone(),
+            total_allocation,
+            top_allocations,
+            allocations,
+            proxy_errors: tally.proxy_errors.clone(),
+        }
+    }
+}
+
+#[derive(Serialize, Debug)]
+pub struct Report {
+    pub operation: Operation,
+    pub started_at: DateTime<Utc>,
+    pub finished_at: DateTime<Utc>,
+    pub elapsed_secs: f64,
+    #[serde(skip)]
+    pub elapsed: Duration,
+    pub wallets: usize,
+    pub outcomes: BTreeMap<&'static str, usize>,
+    pub total_allocation: TokenAmount,
+    pub top_allocations: Vec<WalletAllocation>,
+    pub allocations: Vec<WalletAllocation>,
+    pub proxy_errors: BTreeMap<String, usize>,
+}
+
+impl Report {
+    pub fn log(&self) {
+        let outcomes = self
+            .outcomes
+            .iter()
+            .map(|(outcome, count)| format!("{outcome}: {count}"))
+            .collect::<Vec<_>>()
+            .join(", ");
+
+        tracing::info!(
+            "Run summary: {} {} wallets in {:.1?} ({})",
+            self.operation,
+            self.wallets,
+            self.elapsed,
+            if outcomes.is_empty() {
+                "nothing to do"
+            } else {
+                &outcomes
+            }
+        );
+        tracing::info!(
+            "Total allocation: {} across {} wallets",
+            self.total_allocation,
+            self.allocations.len()
+        );
+
+        if !self.top_allocations.is_empty() {
+            tracing::info!("Top allocations:");
+            for entry in &self.top_allocations {
+                tracing::info!("{} [{}]: {}", entry.address, entry.event, entry.allocation);
+            }
+        }
+
+        if !self.proxy_errors.is_empty() {
+            tracing::info!("Errors by proxy:");
+            for (proxy, errors) in &self.proxy_errors {
+                tracing::info!("{proxy}: {errors}");
+            }
+        }
+    }
+
+    pub async fn write(&self, path: impl AsRef<Path>) -> eyre::Result<()> {
+        let path = path.as_ref();
+
+        tokio::fs::write(path, serde_json::to_string_pretty(self)?).await?;
+        tracing::info!("Run report written to {}", path.display());
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn summarises_outcomes_allocations_and_proxy_errors() {
+        let summary = RunSummary::new(Operation::Check, 6);
+
+        summary
+            .record("a", "eligible", false, Some("proxy-1"))
+            .await;
+        summary.record("b", "eligible", false, None).await;
+        summary.record("c", "failed", true, Some("proxy-1")).await;
+        summary.record("c", "failed", true, None).await;
+
+        summary
+            .record_allocation("a", "season-1", TokenAmount::new(1_500_000, 6))
+            .await;
+        summary
+            .record_allocation("b", "season-1", TokenAmount::new(25, 1))
+            .await;
+        summary
+            .record_allocation("a", "season-2", TokenAmount::zero(6))
+            .await;
+
+        let report = summary.finish().await;
+
+        assert_eq!(report.wallets, 3);
+        assert_eq!(
+            report.outcomes,
+            BTreeMap::from([("eligible", 2), ("failed", 2)])
+        );
+        assert_eq!(report.total_allocation.to_string(), "4");
+        assert_eq!(
+            report
+                .top_allocations
+                .iter()
+                .map(|entry| entry.address.as_str())
+                .collect::<Vec<_>>(),
+            ["b", "a"]
+        );
+        assert_eq!(
+            report.proxy_errors,
+            BTreeMap::from([("direct".to_string(), 1), ("proxy-1".to_string(), 1)])
+        );
+    }
+}
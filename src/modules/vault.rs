@@ -0,0 +1,80 @@
+use crate::{
+    cli::{VaultCommand, VaultImportArgs, VaultListArgs},
+    config::Config,
+    crypto::{derivation::SecretLine, signer::get_address, vault::Vault},
+    utils::files::secret_lines,
+};
+
+use zeroize::Zeroizing;
+
+use super::{load_wallets, read_vault_password};
+
+pub async fn vault(config: &Config, command: VaultCommand) -> eyre::Result<()> {
+    match command {
+        VaultCommand::Import(args) => import(config, args).await,
+        VaultCommand::List(args) => list(config, args).await,
+    }
+}
+
+async fn import(config: &Config, args: VaultImportArgs) -> eyre::Result<()> {
+    let output = args
+        .output
+        .unwrap_or_else(|| args.input.with_extension("vault"));
+
+    let contents = Zeroizing::new(tokio::fs::read_to_string(&args.input).await?);
+    if Vault::parse(&contents)?.is_some() {
+        eyre::bail!("{} is already a vault", args.input.display());
+    }
+
+    let secrets = secret_lines(&contents);
+    if secrets.is_empty() {
+        eyre::bail!("{} has no secrets to import", args.input.display());
+    }
+
+    for (index, secret) in secrets.iter().enumerate() {
+        SecretLine::parse(secret).map_err(|e| eyre::eyre!("Secret at index {index}: {e}"))?;
+    }
+
+    let password = read_vault_password(
+        &config.vault_password,
+        &format!("Password for vault {}", output.display()),
+        true,
+    )?;
+
+    let plaintext = Zeroizing::new(
+        secrets
+            .iter()
+            .map(|secret| secret.as_str())
+            .collect::<Vec<_>>()
+            .join("\n"),
+    );
+
+    Vault::seal(&plaintext, &password)?.write(&output).await?;
+
+    tracing::info!(
+        "Encrypted {} secrets from {} into {}",
+        secrets.len(),
+        args.input.display(),
+        output.display()
+    );
+
+    if args.remove_plaintext {
+        tokio::fs::remove_file(&args.input).await?;
+        tracing::info!("Removed plaintext secrets file {}", args.input.display());
+    } else {
+        tracing::warn!(
+            "{} still holds the secrets in plaintext, remove it once the vault is verified with `vault list`",
+            args.input.display()
+        );
+    }
+
+    Ok(())
+}
+
+async fn list(config: &Config, args: VaultListArgs) -> eyre::Result<()> {
+    for wallet in load_wallets(&args.vault, config).await? {
+        println!("{}", get_address(&wallet));
+    }
+
+    Ok(())
+}
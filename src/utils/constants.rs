@@ -1,4 +1,13 @@
 pub const SECRETS_FILE_PATH: &str = "data/secrets.txt";
 pub const CLAIM_SECRETS_FILE_PATH: &str = "data/claim_secrets.txt";
 pub const ELIGIBLE_FILE_PATH: &str = "data/eligible.txt";
+pub const NOT_ELIGIBLE_FILE_PATH: &str = "data/not_eligible.txt";
+pub const FAILED_FILE_PATH: &str = "data/failed.txt";
 pub const PROXIES_FILE_PATH: &str = "data/proxies.txt";
+pub const STATE_FILE_PATH: &str = "data/state.jsonl";
+pub const CHECK_REPORT_FILE_PATH: &str = "data/check_report.json";
+pub const LINK_REPORT_FILE_PATH: &str = "data/link_report.json";
+pub const HEADERS_FILE_PATH: &str = "data/headers.toml";
+pub const ALLOCATION_EVENT: &str = "tge-airdrop-final";
+pub const CHAIN: &str = "sol";
+pub const ALLOCATION_DECIMALS: u32 = 6;
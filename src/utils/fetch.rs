@@ -1,42 +1,65 @@
-use std::{collections::HashMap, sync::Arc};
+use std::{collections::HashMap, sync::Arc, time::Instant};
 
-use reqwest::{cookie::Jar, header::HeaderMap, Method};
+use reqwest::{cookie::Jar, header::HeaderMap, Method, Proxy};
 use serde::{de::DeserializeOwned, Serialize};
 
+use crate::me::error::ApiError;
+
+#[derive(Clone)]
+pub struct HttpClient {
+    inner: reqwest::Client,
+    proxy_label: Option<String>,
+}
+
+impl HttpClient {
+    pub fn new(proxy: Option<(&str, &Proxy)>, cookie_jar: Arc<Jar>) -> Self {
+        let client_builder = reqwest::Client::builder().cookie_provider(cookie_jar);
+        let client_builder = if let Some((_, proxy)) = proxy {
+            client_builder.proxy(proxy.clone())
+        } else {
+            client_builder
+        };
+
+        let inner = client_builder.build().unwrap_or_else(|err| {
+            tracing::error!("Failed to build a client with cookies. Error: {err}");
+            reqwest::Client::new()
+        });
+
+        Self {
+            inner,
+            proxy_label: proxy.map(|(label, _)| label.to_string()),
+        }
+    }
+}
+
 #[derive(Clone)]
 pub struct RequestParams<'a, S: Serialize> {
     pub url: &'a str,
     pub method: Method,
     pub body: Option<S>,
     pub query_args: Option<HashMap<&'a str, &'a str>>,
-    pub proxy: Option<&'a reqwest::Proxy>,
     pub headers: Option<HeaderMap>,
 }
 
+#[tracing::instrument(
+    name = "request",
+    skip_all,
+    fields(
+        method = %request_params.method,
+        endpoint = %request_params.url,
+        status = tracing::field::Empty,
+        latency_ms = tracing::field::Empty,
+    )
+)]
 pub async fn send_http_request<R: DeserializeOwned>(
     request_params: RequestParams<'_, impl Serialize>,
-    cookie_jar: Option<Arc<Jar>>,
-) -> eyre::Result<Option<R>> {
-    let client_builder = reqwest::Client::builder();
-    let client = if let Some(proxy) = request_params.proxy {
-        client_builder.proxy(proxy.clone())
-    } else {
-        client_builder
-    };
+    client: &HttpClient,
+) -> Result<Option<R>, ApiError> {
+    let proxy_label = client.proxy_label.as_deref().unwrap_or("No proxy");
 
-    let client = if let Some(jar) = cookie_jar.clone() {
-        client.cookie_provider(jar).build().unwrap_or_else(|err| {
-            tracing::error!("Failed to build a client with cookies. Error: {err}");
-            reqwest::Client::new()
-        })
-    } else {
-        client.build().unwrap_or_else(|err| {
-            tracing::error!("Failed to build a client. Error: {err}");
-            reqwest::Client::new()
-        })
-    };
-
-    let mut request = client.request(request_params.method.clone(), request_params.url);
+    let mut request = client
+        .inner
+        .request(request_params.method.clone(), request_params.url);
 
     if let Some(params) = &request_params.query_args {
         request = request.query(&params);
@@ -50,16 +73,13 @@ pub async fn send_http_request<R: DeserializeOwned>(
         request = request.headers(headers.clone());
     }
 
-    let response = request.send().await.inspect_err(|e| {
-        tracing::error!(
-            "Request failed: {}. Proxy: {:?}",
-            e,
-            match request_params.proxy {
-                Some(p) => format!("{:?}", p),
-                None => "No proxy".to_string(),
-            }
-        )
-    })?;
+    let started = Instant::now();
+
+    let response = request
+        .send()
+        .await
+        .inspect_err(|e| tracing::error!("Request failed: {}. Proxy: {}", e, proxy_label))
+        .map_err(|e| ApiError::from_request_error(e, client.proxy_label.is_some()))?;
 
     let response_headers = response.headers().clone();
     let status = response.status();
@@ -67,19 +87,24 @@ pub async fn send_http_request<R: DeserializeOwned>(
     let text = response
         .text()
         .await
-        .inspect_err(|e| tracing::error!("Failed to retrieve response text: {}", e))?;
+        .inspect_err(|e| tracing::error!("Failed to retrieve response text: {}", e))
+        .map_err(ApiError::Transport)?;
+
+    let latency_ms = started.elapsed().as_millis() as u64;
+    let span = tracing::Span::current();
+    span.record("status", status.as_u16());
+    span.record("latency_ms", latency_ms);
+
+    tracing::debug!("Request finished");
 
     if !status.is_success() {
         tracing::error!(
-            "Request failed with status: {}. Response text: {}. Proxy: {:?}",
+            "Request failed with status: {}. Response text: {}. Proxy: {}",
             status,
             text,
-            match request_params.proxy {
-                Some(p) => format!("{:?}", p),
-                None => "No proxy".to_string(),
-            }
+            proxy_label
         );
-        eyre::bail!("HTTP error: {status} - {text}");
+        return Err(ApiError::from_status(status, text));
     }
 
     let content_type = response_headers
@@ -96,7 +121,11 @@ pub async fn send_http_request<R: DeserializeOwned>(
             let json_value = serde_json::json!(text);
             serde_json::from_value::<R>(json_value)
         }
-        .inspect_err(|e| tracing::error!("Failed to deserialize response: {}\n {} ", e, text))?;
+        .inspect_err(|e| tracing::error!("Failed to deserialize response: {}\n {} ", e, text))
+        .map_err(|source| ApiError::Deserialize {
+            source,
+            body: text.clone(),
+        })?;
 
         Some(deserialized)
     };
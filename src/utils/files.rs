@@ -1,18 +1,10 @@
-use std::path::Path;
-
-use tokio::io::AsyncBufReadExt;
-
-pub async fn read_file_lines(path: impl AsRef<Path>) -> eyre::Result<Vec<String>> {
-    let file = tokio::fs::read(path).await?;
-    let mut lines = file.lines();
-
-    let mut contents = vec![];
-    while let Some(line) = lines.next_line().await? {
-        let trimmed = line.trim();
-        if !trimmed.is_empty() {
-            contents.push(trimmed.to_string());
-        }
-    }
-
-    Ok(contents)
+use zeroize::Zeroizing;
+
+pub fn secret_lines(contents: &str) -> Vec<Zeroizing<String>> {
+    contents
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .map(|line| Zeroizing::new(line.to_string()))
+        .collect()
 }
@@ -0,0 +1,220 @@
+use std::{
+    collections::HashMap,
+    fmt,
+    path::{Path, PathBuf},
+};
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use tokio::{
+    fs::{File, OpenOptions},
+    io::AsyncWriteExt,
+    sync::Mutex,
+};
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum Operation {
+    Check,
+    Link,
+}
+
+impl fmt::Display for Operation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Operation::Check => write!(f, "check"),
+            Operation::Link => write!(f, "link"),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum Status {
+    Pending,
+    Done,
+    Failed,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct Entry {
+    pub operation: Operation,
+    pub event: String,
+    pub address: String,
+    pub status: Status,
+    pub updated_at: DateTime<Utc>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+struct State {
+    entries: HashMap<String, Entry>,
+    file: File,
+}
+
+pub struct Journal {
+    path: PathBuf,
+    state: Mutex<State>,
+}
+
+fn key(operation: Operation, event: &str, address: &str) -> String {
+    format!("{operation}:{event}:{address}")
+}
+
+fn parse_entries(contents: &str) -> HashMap<String, Entry> {
+    contents
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .filter_map(|line| match serde_json::from_str::<Entry>(line) {
+            Ok(entry) => Some(entry),
+            Err(e) => {
+                tracing::warn!("Skipping unreadable run journal record: {e}");
+                None
+            }
+        })
+        .map(|entry| (key(entry.operation, &entry.event, &entry.address), entry))
+        .collect()
+}
+
+fn to_line(entry: &Entry) -> eyre::Result<String> {
+    Ok(format!("{}\n", serde_json::to_string(entry)?))
+}
+
+impl Journal {
+    pub async fn open(path: impl AsRef<Path>) -> eyre::Result<Self> {
+        let path = path.as_ref().to_path_buf();
+
+        let entries = match tokio::fs::read_to_string(&path).await {
+            Ok(contents) => parse_entries(&contents),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => HashMap::new(),
+            Err(e) => return Err(e.into()),
+        };
+        let file = compact(&path, &entries).await?;
+
+        Ok(Self {
+            path,
+            state: Mutex::new(State { entries, file }),
+        })
+    }
+
+    pub async fn is_done(&self, operation: Operation, event: &str, address: &str) -> bool {
+        self.state
+            .lock()
+            .await
+            .entries
+            .get(&key(operation, event, address))
+            .is_some_and(|entry| entry.status == Status::Done)
+    }
+
+    pub async fn mark(
+        &self,
+        operation: Operation,
+        event: &str,
+        address: &str,
+        status: Status,
+        error: Option<String>,
+    ) -> eyre::Result<()> {
+        let entry = Entry {
+            operation,
+            event: event.to_string(),
+            address: address.to_string(),
+            status,
+            updated_at: Utc::now(),
+            error,
+        };
+        let line = to_line(&entry)?;
+
+        let mut state = self.state.lock().await;
+        state.file.write_all(line.as_bytes()).await?;
+        state.file.flush().await?;
+        state.entries.insert(key(operation, event, address), entry);
+
+        Ok(())
+    }
+
+    pub async fn reset(&self, operation: Operation) -> eyre::Result<()> {
+        let mut state = self.state.lock().await;
+        state
+            .entries
+            .retain(|_, entry| entry.operation != operation);
+        state.file = compact(&self.path, &state.entries).await?;
+
+        Ok(())
+    }
+}
+
+async fn compact(path: &Path, entries: &HashMap<String, Entry>) -> eyre::Result<File> {
+    let mut sorted: Vec<&Entry> = entries.values().collect();
+    sorted.sort_by(|a, b| {
+        (a.operation as u8, &a.event, &a.address).cmp(&(b.operation as u8, &b.event, &b.address))
+    });
+
+    let mut contents = String::new();
+    for entry in sorted {
+        contents.push_str(&to_line(entry)?);
+    }
+
+    let tmp_path = path.with_extension("tmp");
+    tokio::fs::write(&tmp_path, contents).await?;
+    tokio::fs::rename(&tmp_path, path).await?;
+
+    Ok(OpenOptions::new().append(true).open(path).await?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn appends_records_and_compacts_on_open() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("state.jsonl");
+
+        let journal = Journal::open(&path).await.unwrap();
+        journal
+            .mark(Operation::Check, "season-1", "a", Status::Pending, None)
+            .await
+            .unwrap();
+        journal
+            .mark(Operation::Check, "season-1", "a", Status::Done, None)
+            .await
+            .unwrap();
+        journal
+            .mark(
+                Operation::Link,
+                "season-1",
+                "b",
+                Status::Failed,
+                Some("boom".into()),
+            )
+            .await
+            .unwrap();
+        drop(journal);
+
+        let contents = std::fs::read_to_string(&path).unwrap();
+        assert_eq!(contents.lines().count(), 3);
+
+        let journal = Journal::open(&path).await.unwrap();
+        assert!(journal.is_done(Operation::Check, "season-1", "a").await);
+        assert!(!journal.is_done(Operation::Link, "season-1", "b").await);
+        assert_eq!(std::fs::read_to_string(&path).unwrap().lines().count(), 2);
+
+        journal.reset(Operation::Check).await.unwrap();
+        assert!(!journal.is_done(Operation::Check, "season-1", "a").await);
+        assert_eq!(std::fs::read_to_string(&path).unwrap().lines().count(), 1);
+    }
+
+    #[tokio::test]
+    async fn skips_torn_records() {
+        let dir = tempfile::tempdir().unwrap();
+
+        let torn = dir.path().join("torn.jsonl");
+        std::fs::write(
+            &torn,
+            "{\"operation\":\"check\",\"event\":\"season-1\",\"address\":\"a\",\"status\":\"done\",\"updated_at\":\"2024-01-01T00:00:00Z\"}\n{\"operation\":\"ch",
+        )
+        .unwrap();
+        let journal = Journal::open(&torn).await.unwrap();
+        assert!(journal.is_done(Operation::Check, "season-1", "a").await);
+    }
+}
@@ -3,7 +3,11 @@ use tracing_appender::{
     rolling::{RollingFileAppender, Rotation},
 };
 use tracing_subscriber::{
-    filter::LevelFilter, fmt, layer::SubscriberExt, util::SubscriberInitExt, Layer,
+    filter::{LevelFilter, Targets},
+    fmt,
+    layer::SubscriberExt,
+    util::SubscriberInitExt,
+    Layer,
 };
 
 const LOGS_FOLDER_PATH: &str = "data/logs";
@@ -19,7 +23,10 @@ fn init_logger(logs_folder_path: &str) -> WorkerGuard {
     let (writer, guard) = tracing_appender::non_blocking(file_appender);
 
     let stdout_filter = LevelFilter::INFO;
-    let file_filter = LevelFilter::INFO;
+    // The file also keeps the per request debug events of this crate, without dependency noise
+    let file_filter = Targets::new()
+        .with_default(LevelFilter::INFO)
+        .with_target(env!("CARGO_CRATE_NAME"), LevelFilter::DEBUG);
 
     let stdout_layer = fmt::layer()
         .with_writer(std::io::stdout)
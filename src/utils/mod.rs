@@ -1,4 +1,10 @@
 pub mod constants;
 pub mod fetch;
 pub mod files;
+pub mod journal;
 pub mod logger;
+pub mod proxy;
+pub mod proxy_pool;
+pub mod rate_limiter;
+pub mod retry;
+pub mod session;
@@ -0,0 +1,198 @@
+use std::{
+    path::Path,
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+use reqwest::Proxy;
+use serde::Deserialize;
+
+use crate::me::error::ApiError;
+
+use super::{proxy::load_proxies, rate_limiter::RateLimiter};
+
+#[derive(Deserialize, Clone, Debug)]
+#[serde(default)]
+pub struct ProxyPoolSettings {
+    pub quarantine_after: u32,
+    pub quarantine_secs: u64,
+}
+
+impl Default for ProxyPoolSettings {
+    fn default() -> Self {
+        Self {
+            quarantine_after: 3,
+            quarantine_secs: 300,
+        }
+    }
+}
+
+#[derive(Default, Clone, Debug)]
+pub struct ProxyStats {
+    pub successes: u64,
+    pub failures: u64,
+    pub consecutive_failures: u32,
+    pub quarantines: u32,
+    pub total_latency: Duration,
+    pub quarantined_until: Option<Instant>,
+}
+
+impl ProxyStats {
+    pub fn average_latency(&self) -> Option<Duration> {
+        (self.successes > 0).then(|| self.total_latency / self.successes as u32)
+    }
+
+    fn is_quarantined(&self, now: Instant) -> bool {
+        self.quarantined_until.is_some_and(|until| until > now)
+    }
+}
+
+pub struct ProxyEntry {
+    pub label: String,
+    pub proxy: Proxy,
+    stats: Mutex<ProxyStats>,
+}
+
+pub struct ProxyPool {
+    entries: Vec<ProxyEntry>,
+    settings: ProxyPoolSettings,
+    rate_limiter: Option<RateLimiter>,
+}
+
+impl ProxyPool {
+    pub fn new(proxies: Vec<(String, Proxy)>, settings: ProxyPoolSettings) -> Self {
+        let entries = proxies
+            .into_iter()
+            .map(|(label, proxy)| ProxyEntry {
+                label,
+                proxy,
+                stats: Mutex::new(ProxyStats::default()),
+            })
+            .collect();
+
+        Self {
+            entries,
+            settings,
+            rate_limiter: None,
+        }
+    }
+
+    pub fn direct(settings: ProxyPoolSettings) -> Self {
+        Self::new(vec![], settings)
+    }
+
+    pub fn with_rate_limit(mut self, requests_per_second: f64) -> Self {
+        self.rate_limiter = RateLimiter::per_second(requests_per_second);
+        self
+    }
+
+    pub async fn load(path: impl AsRef<Path>, settings: ProxyPoolSettings) -> eyre::Result<Self> {
+        let proxies = load_proxies(path)
+            .await?
+            .into_iter()
+            .map(|parsed| (parsed.label, parsed.proxy))
+            .collect();
+
+        Ok(Self::new(proxies, settings))
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn get(&self, slot: usize) -> &ProxyEntry {
+        &self.entries[slot]
+    }
+
+    pub fn pick(&self, start: usize) -> Option<usize> {
+        if self.entries.is_empty() {
+            return None;
+        }
+
+        let now = Instant::now();
+        let len = self.entries.len();
+
+        let healthy = (0..len)
+            .map(|offset| (start + offset) % len)
+            .find(|&slot| !self.stats(slot).is_quarantined(now));
+
+        Some(healthy.unwrap_or_else(|| {
+            (0..len)
+                .min_by_key(|&slot| self.stats(slot).quarantined_until)
+                .unwrap_or(start % len)
+        }))
+    }
+
+    pub fn report_success(&self, slot: usize, latency: Duration) {
+        let mut stats = self.entries[slot].stats.lock().unwrap();
+        stats.successes += 1;
+        stats.consecutive_failures = 0;
+        stats.total_latency += latency;
+    }
+
+    pub fn report_failure(&self, slot: usize, error: &ApiError) {
+        let entry = &self.entries[slot];
+        let mut stats = entry.stats.lock().unwrap();
+        stats.failures += 1;
+
+        if !error.should_rotate_proxy() {
+            return;
+        }
+
+        stats.consecutive_failures += 1;
+
+        if stats.consecutive_failures >= self.settings.quarantine_after {
+            stats.consecutive_failures = 0;
+            stats.quarantines += 1;
+            stats.quarantined_until =
+                Some(Instant::now() + Duration::from_secs(self.settings.quarantine_secs));
+
+            tracing::warn!(
+                "Proxy {} quarantined for {}s after repeated failures: {error}",
+                entry.label,
+                self.settings.quarantine_secs
+            );
+        }
+    }
+
+    pub async fn throttle(&self) {
+        if let Some(rate_limiter) = &self.rate_limiter {
+            rate_limiter.acquire().await;
+        }
+    }
+
+    pub fn stats(&self, slot: usize) -> ProxyStats {
+        self.entries[slot].stats.lock().unwrap().clone()
+    }
+
+    pub fn log_summary(&self) {
+        if self.entries.is_empty() {
+            return;
+        }
+
+        let now = Instant::now();
+
+        tracing::info!("Proxy health summary:");
+        for (slot, entry) in self.entries.iter().enumerate() {
+            let stats = self.stats(slot);
+            let latency = stats
+                .average_latency()
+                .map(|latency| format!("{}ms", latency.as_millis()))
+                .unwrap_or_else(|| "-".to_string());
+
+            tracing::info!(
+                "{}: {} ok, {} failed, avg latency {}, quarantined {} times{}",
+                entry.label,
+                stats.successes,
+                stats.failures,
+                latency,
+                stats.quarantines,
+                if stats.is_quarantined(now) {
+                    " (currently quarantined)"
+                } else {
+                    ""
+                }
+            );
+        }
+    }
+}
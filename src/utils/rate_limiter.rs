@@ -0,0 +1,60 @@
+use std::time::Duration;
+
+use tokio::{sync::Mutex, time::Instant};
+
+// Slower rates would need an interval longer than Duration can hold
+const MIN_RATE: f64 = 0.000_001;
+
+pub fn validate_rate(rate: f64) -> eyre::Result<()> {
+    if rate.is_nan() || rate.is_infinite() || (rate > 0.0 && rate < MIN_RATE) {
+        eyre::bail!("must be 0 or a finite number of at least {MIN_RATE} requests per second");
+    }
+
+    Ok(())
+}
+
+pub struct RateLimiter {
+    interval: Duration,
+    next_slot: Mutex<Instant>,
+}
+
+impl RateLimiter {
+    pub fn per_second(rate: f64) -> Option<Self> {
+        if rate <= 0.0 {
+            return None;
+        }
+
+        Some(Self {
+            interval: Duration::from_secs_f64(1.0 / rate),
+            next_slot: Mutex::new(Instant::now()),
+        })
+    }
+
+    pub async fn acquire(&self) {
+        let slot = {
+            let mut next_slot = self.next_slot.lock().await;
+            let slot = (*next_slot).max(Instant::now());
+            *next_slot = slot + self.interval;
+            slot
+        };
+
+        tokio::time::sleep_until(slot).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rejects_rates_without_a_valid_interval() {
+        for rate in [0.0, 0.5, 10.0, MIN_RATE] {
+            assert!(validate_rate(rate).is_ok(), "{rate}");
+            assert!(rate == 0.0 || RateLimiter::per_second(rate).is_some());
+        }
+
+        for rate in [f64::NAN, f64::INFINITY, 1e-30] {
+            assert!(validate_rate(rate).is_err(), "{rate}");
+        }
+    }
+}
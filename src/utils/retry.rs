@@ -0,0 +1,89 @@
+use std::{future::Future, time::Duration};
+
+use rand::Rng;
+use serde::Deserialize;
+
+use crate::me::error::{ApiError, ErrorClass};
+
+#[derive(Deserialize, Clone, Debug)]
+#[serde(default)]
+pub struct RetryPolicy {
+    pub max_attempts: u32,
+    pub base_delay_ms: u64,
+    pub max_delay_ms: u64,
+    pub jitter: f64,
+    pub retryable: Vec<ErrorClass>,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 5,
+            base_delay_ms: 1_000,
+            max_delay_ms: 30_000,
+            jitter: 0.2,
+            retryable: vec![
+                ErrorClass::Transport,
+                ErrorClass::Proxy,
+                ErrorClass::ServerError,
+                ErrorClass::RateLimited,
+                ErrorClass::Forbidden,
+                ErrorClass::Unauthorized,
+            ],
+        }
+    }
+}
+
+impl RetryPolicy {
+    pub fn without(&self, class: ErrorClass) -> Self {
+        let mut policy = self.clone();
+        policy.retryable.retain(|retryable| *retryable != class);
+
+        policy
+    }
+
+    pub fn is_retryable(&self, error: &ApiError) -> bool {
+        self.retryable.contains(&error.class())
+    }
+
+    pub fn delay(&self, attempt: u32) -> Duration {
+        let exponential = self
+            .base_delay_ms
+            .saturating_mul(2u64.saturating_pow(attempt.saturating_sub(1)));
+        let capped = exponential.min(self.max_delay_ms) as f64;
+
+        let jitter = self.jitter.clamp(0.0, 1.0);
+        let factor = if jitter > 0.0 {
+            rand::thread_rng().gen_range(1.0 - jitter..=1.0 + jitter)
+        } else {
+            1.0
+        };
+
+        Duration::from_millis((capped * factor) as u64)
+    }
+
+    pub async fn retry<T, F, Fut>(&self, operation: &str, mut f: F) -> Result<T, ApiError>
+    where
+        F: FnMut(u32) -> Fut,
+        Fut: Future<Output = Result<T, ApiError>>,
+    {
+        let max_attempts = self.max_attempts.max(1);
+        let mut attempt = 1;
+
+        loop {
+            match f(attempt).await {
+                Ok(value) => return Ok(value),
+                Err(e) if attempt < max_attempts && self.is_retryable(&e) => {
+                    let delay = self.delay(attempt);
+                    tracing::warn!(
+                        "{operation} failed (attempt {attempt}/{max_attempts}): {e}. Retrying in {}ms",
+                        delay.as_millis()
+                    );
+                    tokio::time::sleep(delay).await;
+                    attempt += 1;
+                }
+                Err(e) => return Err(e),
+            }
+        }
+    }
+}
@@ -0,0 +1,145 @@
+use std::{
+    collections::HashMap,
+    future::Future,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc, Mutex,
+    },
+    time::Instant,
+};
+
+use reqwest::cookie::Jar;
+use tracing::Span;
+
+use crate::me::{context::ApiContext, error::ApiError};
+
+use super::{
+    fetch::HttpClient,
+    proxy_pool::{ProxyEntry, ProxyPool},
+};
+
+pub struct Session<'a> {
+    pool: &'a ProxyPool,
+    api: &'a ApiContext,
+    slot: AtomicUsize,
+    last_slot: AtomicUsize,
+    cookie_jar: Arc<Jar>,
+    clients: Mutex<HashMap<usize, HttpClient>>,
+    wallet_span: Span,
+}
+
+impl<'a> Session<'a> {
+    pub fn new(pool: &'a ProxyPool, api: &'a ApiContext, wallet_index: usize) -> Self {
+        let slot = pool.pick(wallet_index).unwrap_or_default();
+
+        Self {
+            pool,
+            api,
+            slot: AtomicUsize::new(slot),
+            last_slot: AtomicUsize::new(slot),
+            cookie_jar: Arc::new(Jar::default()),
+            clients: Mutex::new(HashMap::new()),
+            wallet_span: Span::current(),
+        }
+    }
+
+    fn entry(&self, slot: usize) -> Option<&'a ProxyEntry> {
+        if self.pool.is_empty() {
+            return None;
+        }
+
+        Some(self.pool.get(slot))
+    }
+
+    pub fn api(&self) -> &'a ApiContext {
+        self.api
+    }
+
+    // The proxy of the latest request, a failure may already have rotated the next one away
+    pub fn last_proxy_label(&self) -> Option<&'a str> {
+        self.entry(self.last_slot.load(Ordering::Relaxed))
+            .map(|entry| entry.label.as_str())
+    }
+
+    fn client(&self, slot: usize) -> HttpClient {
+        let mut clients = self.clients.lock().unwrap();
+
+        clients
+            .entry(slot)
+            .or_insert_with(|| {
+                HttpClient::new(
+                    self.entry(slot)
+                        .map(|entry| (entry.label.as_str(), &entry.proxy)),
+                    Arc::clone(&self.cookie_jar),
+                )
+            })
+            .clone()
+    }
+
+    pub async fn call<T, F, Fut>(&self, f: F) -> Result<T, ApiError>
+    where
+        F: FnOnce(HttpClient) -> Fut,
+        Fut: Future<Output = Result<T, ApiError>>,
+    {
+        self.pool.throttle().await;
+
+        let slot = self.slot.load(Ordering::Relaxed);
+        self.last_slot.store(slot, Ordering::Relaxed);
+        let client = self.client(slot);
+
+        let proxy_label = self
+            .entry(slot)
+            .map_or("direct", |entry| entry.label.as_str());
+        self.wallet_span.record("proxy", proxy_label);
+
+        let started = Instant::now();
+        let result = f(client).await;
+
+        if self.pool.is_empty() {
+            return result;
+        }
+
+        match &result {
+            Ok(_) => self.pool.report_success(slot, started.elapsed()),
+            Err(e) => {
+                self.pool.report_failure(slot, e);
+
+                if e.should_rotate_proxy() {
+                    if let Some(next) = self.pool.pick(slot + 1) {
+                        self.slot.store(next, Ordering::Relaxed);
+                    }
+                }
+            }
+        }
+
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use reqwest::{Proxy, StatusCode};
+
+    use crate::utils::proxy_pool::ProxyPoolSettings;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn failures_keep_the_proxy_that_served_them() {
+        let proxies = ["http://127.0.0.1:1", "http://127.0.0.1:2"]
+            .map(|url| (url.to_string(), Proxy::all(url).unwrap()));
+        let pool = ProxyPool::new(proxies.into(), ProxyPoolSettings::default());
+        let api = ApiContext::default();
+        let session = Session::new(&pool, &api, 0);
+
+        let result: Result<(), ApiError> = session
+            .call(|_| async { Err(ApiError::from_status(StatusCode::FORBIDDEN, String::new())) })
+            .await;
+
+        assert!(result.is_err());
+        assert_eq!(session.last_proxy_label(), Some("http://127.0.0.1:1"));
+
+        session.call(|_| async { Ok(()) }).await.unwrap();
+        assert_eq!(session.last_proxy_label(), Some("http://127.0.0.1:2"));
+    }
+}